@@ -3,13 +3,22 @@ use clap::{Parser, Subcommand};
 
 use crate::{
     commands::{
+        collection,
         connect::Connect,
         context::Context,
+        daemon::Daemon,
         init::Init,
         launch::Launch,
-        mods::{downloads::Downloads, install::Install, nxm::NxmHandler},
+        mods::{
+            add::Add, conflicts::Conflicts, downloads::Downloads, fetch::Fetch, history::History,
+            info::Info, install::Install, nxm::NxmHandler, order::Order, retry::Retry, undo::Undo,
+            uninstall::Uninstall, updates::Updates,
+        },
+        plugins::{disable::Disable, enable::Enable, list::List, reorder::Reorder},
+        sink::{self, clear::Clear, promote::Promote},
     },
     config::Config,
+    games::Game,
     usage_for,
     utils::state::State,
 };
@@ -35,10 +44,18 @@ pub enum Command {
     Connect(Connect),
     #[command(name= Cli::CONTEXT, about = "Sets the current active game context")]
     Context(Context),
+    #[command(name = Cli::DAEMON, about = "Runs the download queue that nxm links are handed to")]
+    Daemon(Daemon),
     #[command(name = Cli::NXM, hide = true)]
     NxmHandler(NxmHandler),
     #[command(subcommand, name = Cli::MOD, about = "All commands to manage mods with")]
     Mods(ModsCommand),
+    #[command(subcommand, name = Cli::COLLECTION, about = "All commands to install Nexus collections with")]
+    Collection(CollectionCommand),
+    #[command(subcommand, name = Cli::PLUGINS, about = "All commands to manage plugins and their load order with")]
+    Plugins(PluginsCommand),
+    #[command(subcommand, name = Cli::SINK, about = "All commands to manage files written by the game and its tools")]
+    Sink(SinkCommand),
 }
 
 #[derive(Subcommand)]
@@ -47,6 +64,54 @@ pub enum ModsCommand {
     Downloads(Downloads),
     #[command(name = Cli::MOD_INSTALL, about = "Installs mods from your staging directory.")]
     Install(Install),
+    #[command(name = Cli::MOD_UNINSTALL, about = "Uninstalls mods and removes their installed files.")]
+    Uninstall(Uninstall),
+    #[command(name = Cli::MOD_ORDER, about = "Opens a menu to change which mods win file conflicts.")]
+    Order(Order),
+    #[command(name = Cli::MOD_CONFLICTS, about = "Shows which mods overwrite each other's files.")]
+    Conflicts(Conflicts),
+    #[command(name = Cli::MOD_RETRY, about = "Downloads failed archives again (Nexus premium only).")]
+    Retry(Retry),
+    #[command(name = Cli::MOD_HISTORY, about = "Shows recorded changes to the mod list.")]
+    History(History),
+    #[command(name = Cli::MOD_UNDO, about = "Reverses the last install or uninstall.")]
+    Undo(Undo),
+    #[command(name = Cli::MOD_UPDATES, about = "Checks Nexus and GitHub for newer files of your mods.")]
+    Updates(Updates),
+    #[command(name = Cli::MOD_INFO, about = "Shows what is known about a mod and its archives.")]
+    Info(Info),
+    #[command(name = Cli::MOD_ADD, about = "Adds a mod archive from disk or a GitHub release, ready to be installed.")]
+    Add(Add),
+    #[command(name = Cli::MOD_FETCH, about = "Downloads a mod archive from a url, ready to be installed.")]
+    Fetch(Fetch),
+}
+
+#[derive(Subcommand)]
+pub enum CollectionCommand {
+    #[command(name = Cli::COLLECTION_INSTALL, about = "Downloads and installs every mod of a collection with its choices and load order.")]
+    Install(collection::install::Install),
+}
+
+#[derive(Subcommand)]
+pub enum PluginsCommand {
+    #[command(name = Cli::PLUGINS_LIST, about = "Displays all installed plugins in load order")]
+    List(List),
+    #[command(name = Cli::PLUGINS_ENABLE, about = "Enables plugins so the game loads them")]
+    Enable(Enable),
+    #[command(name = Cli::PLUGINS_DISABLE, about = "Disables plugins so the game skips them")]
+    Disable(Disable),
+    #[command(name = Cli::PLUGINS_REORDER, about = "Opens a menu to change the plugin load order")]
+    Reorder(Reorder),
+}
+
+#[derive(Subcommand)]
+pub enum SinkCommand {
+    #[command(name = Cli::SINK_LIST, about = "Displays all files currently in the sink")]
+    List(sink::list::List),
+    #[command(name = Cli::SINK_CLEAR, about = "Deletes all files in the sink")]
+    Clear(Clear),
+    #[command(name = Cli::SINK_PROMOTE, about = "Turns the sink contents into a new mod")]
+    Promote(Promote),
 }
 
 impl Cli {
@@ -57,10 +122,35 @@ impl Cli {
     pub const CONNECT: &str = "connect";
     pub const CONTEXT: &str = "context";
     pub const NXM: &str = "nxm";
+    pub const DAEMON: &str = "daemon";
     pub const MOD: &str = "mod";
+    pub const COLLECTION: &str = "collection";
+    pub const PLUGINS: &str = "plugins";
+    pub const SINK: &str = "sink";
 
     pub const MOD_DOWNLOADS: &str = "downloads";
     pub const MOD_INSTALL: &str = "install";
+    pub const MOD_UNINSTALL: &str = "uninstall";
+    pub const MOD_ORDER: &str = "order";
+    pub const MOD_CONFLICTS: &str = "conflicts";
+    pub const MOD_RETRY: &str = "retry";
+    pub const MOD_HISTORY: &str = "history";
+    pub const MOD_UNDO: &str = "undo";
+    pub const MOD_UPDATES: &str = "updates";
+    pub const MOD_INFO: &str = "info";
+    pub const MOD_ADD: &str = "add";
+    pub const MOD_FETCH: &str = "fetch";
+
+    pub const COLLECTION_INSTALL: &str = "install";
+
+    pub const PLUGINS_LIST: &str = "list";
+    pub const PLUGINS_ENABLE: &str = "enable";
+    pub const PLUGINS_DISABLE: &str = "disable";
+    pub const PLUGINS_REORDER: &str = "reorder";
+
+    pub const SINK_LIST: &str = "list";
+    pub const SINK_CLEAR: &str = "clear";
+    pub const SINK_PROMOTE: &str = "promote";
 
     pub async fn run(&self, config: &mut Config) -> anyhow::Result<()> {
         match &self.command {
@@ -68,20 +158,50 @@ impl Cli {
             Some(Command::Launch(cmd)) => cmd.run(config),
             Some(Command::Connect(cmd)) => cmd.run().await,
             Some(Command::Context(cmd)) => cmd.run(config),
-            Some(Command::NxmHandler(cmd)) => cmd.run(config).await,
+            Some(Command::NxmHandler(cmd)) => cmd.run(),
+            Some(Command::Daemon(cmd)) => cmd.run(config).await,
             Some(Command::Mods(cmd)) => {
-                let state = State::new(config.state_file());
-                let current_context = match state.current_context()? {
-                    Some(context) => context,
-                    None => bail!(
-                        "Game context required for mod commands (Try: '{}')",
-                        usage_for!(Cli::CONTEXT)
-                    ),
-                };
+                let current_context = Self::require_context(config, "mod")?;
 
                 match cmd {
                     ModsCommand::Install(cmd) => cmd.run(config, &current_context),
                     ModsCommand::Downloads(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Uninstall(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Order(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Conflicts(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Retry(cmd) => cmd.run(config, &current_context).await,
+                    ModsCommand::History(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Undo(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Updates(cmd) => cmd.run(config, &current_context).await,
+                    ModsCommand::Info(cmd) => cmd.run(config, &current_context),
+                    ModsCommand::Add(cmd) => cmd.run(config, &current_context).await,
+                    ModsCommand::Fetch(cmd) => cmd.run(config, &current_context).await,
+                }
+            }
+            Some(Command::Collection(cmd)) => {
+                let current_context = Self::require_context(config, "collection")?;
+
+                match cmd {
+                    CollectionCommand::Install(cmd) => cmd.run(config, &current_context).await,
+                }
+            }
+            Some(Command::Plugins(cmd)) => {
+                let current_context = Self::require_context(config, "plugin")?;
+
+                match cmd {
+                    PluginsCommand::List(cmd) => cmd.run(config, &current_context),
+                    PluginsCommand::Enable(cmd) => cmd.run(config, &current_context),
+                    PluginsCommand::Disable(cmd) => cmd.run(config, &current_context),
+                    PluginsCommand::Reorder(cmd) => cmd.run(config, &current_context),
+                }
+            }
+            Some(Command::Sink(cmd)) => {
+                let current_context = Self::require_context(config, "sink")?;
+
+                match cmd {
+                    SinkCommand::List(cmd) => cmd.run(config, &current_context),
+                    SinkCommand::Clear(cmd) => cmd.run(config, &current_context),
+                    SinkCommand::Promote(cmd) => cmd.run(config, &current_context),
                 }
             }
             None => {
@@ -92,4 +212,17 @@ impl Cli {
             }
         }
     }
+
+    fn require_context(config: &Config, command_kind: &str) -> anyhow::Result<Game> {
+        let state = State::new(config.state_file());
+
+        match state.current_context()? {
+            Some(context) => Ok(context),
+            None => bail!(
+                "Game context required for {} commands (Try: '{}')",
+                command_kind,
+                usage_for!(Cli::CONTEXT)
+            ),
+        }
+    }
 }
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{
+        download_tracker::DownloadTracker, mod_list_store::ModListStore,
+        plugin_list_store::PluginListStore,
+    },
+    sources::nexus::{
+        self,
+        collection::{self, CollectionManifest, CollectionMod, ManifestChooser},
+    },
+    types::FileStatus,
+    usage_for,
+};
+
+#[derive(Args)]
+pub struct Install {
+    /// Collection slug or nxm link, e.g. 'qdurkx' or 'nxm://skyrimspecialedition/collections/qdurkx/revisions/12'.
+    pub collection: String,
+
+    /// Also installs the mods the curator marked as optional.
+    #[arg(long)]
+    pub optional: bool,
+}
+
+impl Install {
+    pub async fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let link = collection::parse_collection_link(&self.collection)?;
+        if let Some(domain) = &link.game
+            && domain != nexus::to_nexus_domain(current_game)?
+        {
+            bail!(
+                "The collection is for '{}', switch the game with '{}' first",
+                domain,
+                usage_for!(Cli::CONTEXT)
+            );
+        }
+
+        let nexus_config = match nexus::Config::load() {
+            Ok(config) => config,
+            Err(_) => bail!(
+                "Nexus connection is not set up. Run '{}'",
+                usage_for!("connect")
+            ),
+        };
+
+        // Without the key of an nxm link, Nexus only hands out download links to premium accounts.
+        let (account, _) = nexus::Nexus::status(&nexus_config).await?;
+        if !account.is_premium {
+            bail!(
+                "Installing collections requires a Nexus premium account, free accounts have to download each mod from the collection page"
+            );
+        }
+
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace.clone(), mod_list_store.clone());
+        let api = nexus::Nexus::new(&nexus_config, download_tracker)?;
+
+        let manifest = api
+            .get_collection(
+                current_game,
+                &link,
+                &workspace.cache_dir().join("collections"),
+            )
+            .await?;
+
+        println!(
+            "\n{} {}\n",
+            "Installing collection".cyan().bold(),
+            format!("'{}' by {}", manifest.info.name, manifest.info.author).bold()
+        );
+
+        let selected: Vec<&CollectionMod> = manifest
+            .mods
+            .iter()
+            .filter(|m| self.optional || !m.optional)
+            .collect();
+
+        // Mod rules reference archives by md5 or logical file name, both resolve to mod folders.
+        let mut dirs: HashMap<String, String> = HashMap::new();
+        let mut failures = 0;
+
+        for (i, collection_mod) in selected.iter().enumerate() {
+            let prefix = format!("[{}/{}]", i + 1, selected.len())
+                .dimmed()
+                .to_string();
+
+            match install_mod(&api, &mod_list_store, current_game, collection_mod).await {
+                Ok(Some((dir_name, status))) => {
+                    println!("{} {} {}", prefix, collection_mod.name.bold(), status);
+                    for key in [
+                        &collection_mod.source.md5,
+                        &collection_mod.source.logical_filename,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    {
+                        dirs.insert(key.to_lowercase(), dir_name.clone());
+                    }
+                }
+                Ok(None) => println!(
+                    "{} {} {}",
+                    prefix,
+                    collection_mod.name.bold(),
+                    "skipped, not hosted on Nexus".yellow()
+                ),
+                Err(err) => {
+                    failures += 1;
+                    println!(
+                        "{} {} {}: {}",
+                        prefix,
+                        collection_mod.name.bold(),
+                        "failed".red(),
+                        err
+                    );
+                }
+            }
+        }
+
+        apply_order(&manifest, &mod_list_store, &workspace, &dirs)?;
+
+        if failures > 0 {
+            bail!(
+                "{} of {} mod(s) of the collection could not be installed",
+                failures,
+                selected.len()
+            );
+        }
+
+        println!("\n{}", "Collection installed.".green().bold());
+
+        Ok(())
+    }
+}
+
+/// Downloads and installs one mod of the collection, returning its mod folder and what was done.
+/// Returns `None` for mods that aren't hosted on Nexus.
+async fn install_mod(
+    api: &nexus::Nexus,
+    mod_list_store: &ModListStore,
+    collection_game: &Game,
+    collection_mod: &CollectionMod,
+) -> anyhow::Result<Option<(String, &'static str)>> {
+    let (true, Some(mod_id), Some(file_id)) = (
+        collection_mod.is_nexus(),
+        collection_mod.source.mod_id,
+        collection_mod.source.file_id,
+    ) else {
+        return Ok(None);
+    };
+    let game = &collection_mod.game(collection_game)?;
+
+    let m = api.get_mod_info(game, &mod_id.to_string()).await?;
+    let archive = api
+        .get_mod_file_info(game, &mod_id.to_string(), &file_id.to_string())
+        .await?;
+    let dir_name = archive.file_uid.to_string();
+
+    let status = match mod_list_store.get_archive_status(m.uid, archive.file_uid)? {
+        FileStatus::Installed => return Ok(Some((dir_name, "already installed"))),
+        FileStatus::Downloaded => "installed",
+        FileStatus::Unknown | FileStatus::Downloading | FileStatus::Failed(_) => {
+            let url = api
+                .get_premium_download_link(game, m.uid, archive.file_uid)
+                .await?;
+            api.download_archive(mod_list_store, &m, &archive, &url)
+                .await?;
+            "downloaded and installed"
+        }
+    };
+
+    // Staging moved the archive, so the stored entry has the path to install from.
+    let staged = mod_list_store
+        .read()?
+        .mods
+        .into_iter()
+        .find(|stored| stored.uid == m.uid)
+        .and_then(|stored| {
+            stored
+                .archives
+                .into_iter()
+                .find(|a| a.file_uid == archive.file_uid)
+        })
+        .ok_or_else(|| anyhow::anyhow!("'{}' is missing from the mod list", archive.file_name))?;
+
+    let mut chooser = ManifestChooser::new(collection_mod.choices.as_ref());
+    mod_list_store.install_archive(&m, &staged, &mut chooser)?;
+
+    Ok(Some((dir_name, status)))
+}
+
+/// Applies the mod rules and plugin order of the collection to what is installed.
+fn apply_order(
+    manifest: &CollectionManifest,
+    mod_list_store: &ModListStore,
+    workspace: &Workspace,
+    dirs: &HashMap<String, String>,
+) -> anyhow::Result<()> {
+    let mut layers = mod_list_store.layers()?;
+    collection::order_by_rules(&mut layers, &manifest.mod_rules, |reference| {
+        [&reference.file_md5, &reference.logical_file_name]
+            .into_iter()
+            .flatten()
+            .find_map(|key| dirs.get(&key.to_lowercase()).cloned())
+    });
+    mod_list_store.set_priority(&layers)?;
+
+    let plugin_list_store = PluginListStore::new(workspace.clone());
+    let mut plugin_list = plugin_list_store.sync()?;
+    collection::apply_plugin_order(&mut plugin_list, &manifest.plugins);
+    plugin_list_store.write(&plugin_list)?;
+
+    Ok(())
+}
@@ -1,24 +1,37 @@
+use anyhow::bail;
 use clap::Args;
 use owo_colors::OwoColorize;
 
 use crate::{
-    sources::{
-        Source,
-        nexus::{self, Config},
-    },
+    sources::nexus::{self, Config, RateLimit},
     ui::prompt,
+    usage_for,
 };
 
+/// Mod sources that need an account, local archives, urls and GitHub releases work without one.
+#[derive(clap::ValueEnum, Clone)]
+pub enum ConnectSource {
+    Nexus,
+}
+
 #[derive(Args)]
 pub struct Connect {
     #[arg(value_enum)]
-    pub source: Source,
+    pub source: ConnectSource,
+
+    /// Shows the state of an existing connection, including remaining API requests.
+    #[arg(long)]
+    pub status: bool,
 }
 
 impl Connect {
     pub async fn run(&self) -> anyhow::Result<()> {
+        if self.status {
+            return self.print_status().await;
+        }
+
         match self.source {
-            Source::Nexus => {
+            ConnectSource::Nexus => {
                 let mut config = match Config::load() {
                     Ok(config) => {
                         if !prompt::confirm(
@@ -42,4 +55,67 @@ impl Connect {
         }
         Ok(())
     }
+
+    async fn print_status(&self) -> anyhow::Result<()> {
+        match self.source {
+            ConnectSource::Nexus => {
+                let Ok(config) = Config::load() else {
+                    bail!(
+                        "Nexus connection is not set up. Run '{}'",
+                        usage_for!("connect")
+                    );
+                };
+
+                let (account, rate_limit) = nexus::Nexus::status(&config).await?;
+                let membership = if account.is_premium {
+                    "premium"
+                } else {
+                    "free"
+                };
+
+                println!(
+                    "{} as '{}' ({})",
+                    "Connected to Nexus".green(),
+                    account.name.bold(),
+                    membership
+                );
+
+                match rate_limit {
+                    Some(rate_limit) => print_rate_limit(&rate_limit),
+                    None => println!("{}", "Nexus reported no rate limit.".italic()),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_rate_limit(rate_limit: &RateLimit) {
+    let remaining = |remaining: Option<u64>, limit: Option<u64>| match (remaining, limit) {
+        (Some(remaining), Some(limit)) => format!("{}/{}", remaining, limit),
+        (Some(remaining), None) => remaining.to_string(),
+        _ => "unknown".to_string(),
+    };
+
+    println!(
+        "Hourly requests left: {} {}",
+        remaining(rate_limit.hourly_remaining, rate_limit.hourly_limit).bold(),
+        rate_limit
+            .hourly_reset
+            .as_ref()
+            .map(|reset| format!("(resets {})", reset))
+            .unwrap_or_default()
+            .dimmed()
+    );
+    println!(
+        "Daily requests left:  {} {}",
+        remaining(rate_limit.daily_remaining, rate_limit.daily_limit).bold(),
+        rate_limit
+            .daily_reset
+            .as_ref()
+            .map(|reset| format!("(resets {})", reset))
+            .unwrap_or_default()
+            .dimmed()
+    );
 }
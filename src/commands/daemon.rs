@@ -0,0 +1,65 @@
+use std::{fs, rc::Rc};
+
+use anyhow::bail;
+use clap::Args;
+use tokio::{net::UnixListener, task::LocalSet};
+
+use crate::{
+    config::Config,
+    daemon::{
+        self, client, job,
+        server::{JobRunner, Server},
+    },
+};
+
+#[derive(Args)]
+pub struct Daemon {
+    /// Maximum number of downloads running at the same time.
+    #[arg(short, long, default_value_t = daemon::DEFAULT_CONCURRENCY)]
+    pub concurrency: usize,
+}
+
+impl Daemon {
+    pub async fn run(&self, config: &Config) -> anyhow::Result<()> {
+        let socket_path = daemon::socket_path();
+
+        if client::is_running(&socket_path) {
+            bail!(
+                "A daemon is already listening on '{}'",
+                socket_path.display()
+            );
+        }
+
+        // A socket left behind by a daemon that didn't shut down cleanly.
+        if socket_path.exists() {
+            fs::remove_file(&socket_path)?;
+        }
+
+        let listener = UnixListener::bind(&socket_path)?;
+        println!(
+            "Listening for downloads on '{}' ({} at a time)",
+            socket_path.display(),
+            self.concurrency
+        );
+
+        let config = config.clone();
+        let runner: JobRunner = Rc::new(move |url, reporter| {
+            let config = config.clone();
+            Box::pin(async move { job::download_nxm_link(&config, &url, &reporter).await })
+        });
+        let server = Server::new(self.concurrency, runner);
+
+        let result = LocalSet::new()
+            .run_until(async {
+                tokio::select! {
+                    result = server.run(listener) => result,
+                    _ = tokio::signal::ctrl_c() => Ok(()),
+                }
+            })
+            .await;
+
+        fs::remove_file(&socket_path)?;
+
+        result
+    }
+}
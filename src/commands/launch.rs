@@ -1,4 +1,4 @@
-use std::{fs, process::Command};
+use std::process::Command;
 
 use anyhow::{Context, bail};
 use clap::Args;
@@ -8,11 +8,10 @@ use crate::{
     cli::Cli,
     config::Config,
     games::{Game, workspace::Workspace},
-    mods::env_store::EnvStore,
+    mods::{env_store::EnvStore, mod_list_store::ModListStore, plugin_list_store::PluginListStore},
     ui::print,
     usage_for,
     utils::{
-        fs::copy_dir,
         os::{
             mount::{Mountable, OverlayMounter},
             permissions,
@@ -60,28 +59,48 @@ impl Launch {
 
         print::print_inline_status(&format!("{}", "Mounting game folders...".bold()))?;
 
-        mounter.unshare_as_private_namespace()?;
-        mounter
-            .mount_overlay()
-            .with_context(|| format!("Could not mount overlay folders for {}", game))?;
-
-        print::print_inline_status(&format!("{}", "Copying mods into mounted folder...".bold()))?;
-
-        for entry in fs::read_dir(context.mods_dir())? {
-            let entry = entry?;
-            if !entry.metadata()?.is_dir() {
+        let mod_list_store = ModListStore::new(context.clone());
+        let mod_dirs: Vec<_> = mod_list_store
+            .layers()?
+            .into_iter()
+            .map(|layer| layer.path)
+            .collect();
+
+        // Directories that only differ in casing would show up twice through overlayfs. Only the
+        // mods are ours to rename, they take the casing of the game folder where it has one.
+        for dir in &mod_dirs {
+            let normalized = mod_list_store.normalize_dir_case(dir)?;
+            for (from, to) in normalized.renamed {
                 println!(
-                    "{} Not a directory, skipping: {}",
-                    "Warning:".yellow(),
-                    entry.path().display()
+                    "Renamed '{}' to '{}' in '{}'",
+                    from.display(),
+                    to.display(),
+                    dir.display()
+                );
+            }
+            for path in normalized.collisions {
+                println!(
+                    "{} '{}' in '{}', a folder with a different casing already has it",
+                    "Kept".yellow(),
+                    path.display(),
+                    dir.display()
                 );
-                continue;
             }
-            copy_dir(&entry.path(), &context.overlay_merged_dir(), true)?;
         }
 
+        mounter.unshare_as_private_namespace()?;
+        mounter
+            .mount_overlay(&mod_dirs)
+            .with_context(|| format!("Could not mount overlay folders for {}", game))?;
+
         permissions::drop_privileges()?;
 
+        let plugin_list_store = PluginListStore::new(context.clone());
+        let plugin_list = plugin_list_store.sync()?;
+        plugin_list_store
+            .write_load_order(&plugin_list)
+            .with_context(|| format!("Could not write load order for {}", game))?;
+
         if !context.proton_binary().exists() {
             bail!(
                 "Proton binary not found at '{}'",
@@ -1,5 +1,9 @@
+pub mod collection;
 pub mod connect;
 pub mod context;
+pub mod daemon;
 pub mod init;
 pub mod launch;
 pub mod mods;
+pub mod plugins;
+pub mod sink;
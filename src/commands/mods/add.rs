@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use anyhow::bail;
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::github::{self, GitHub},
+    types::{Mod, ModArchive},
+    usage_for,
+    utils::fs::{ARCHIVE_EXTENSIONS, ExpandTilde, is_supported_archive},
+};
+
+#[derive(Args)]
+pub struct Add {
+    /// Path to a zip, 7z or rar archive, or 'github:owner/repo' for the latest GitHub release.
+    pub source: String,
+
+    /// Name to show for the mod, defaults to the archive name without extension or the
+    /// repository name.
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Glob picking the release asset, e.g. '*-SE.7z'. Needed when a release has several
+    /// archives, later adds of the repository remember it.
+    #[arg(long)]
+    pub asset_pattern: Option<String>,
+}
+
+impl Add {
+    pub async fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+
+        let (m, archive) = if self.source.starts_with(github::SPEC_PREFIX) {
+            let repository = github::parse_repository(&self.source)?;
+            let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+
+            GitHub::new(download_tracker)?
+                .add_latest_release(
+                    &mod_list_store,
+                    &repository,
+                    self.name.as_deref(),
+                    self.asset_pattern.as_deref(),
+                )
+                .await?
+        } else {
+            if self.asset_pattern.is_some() {
+                bail!("--asset-pattern only applies to GitHub releases");
+            }
+
+            self.add_local_archive(&mod_list_store)?
+        };
+
+        println!(
+            "{} '{}' as {}, install it with '{}'",
+            "Added".green(),
+            archive.file_name,
+            m.name.bold(),
+            usage_for!(Cli::MOD, Cli::MOD_INSTALL)
+        );
+
+        Ok(())
+    }
+
+    fn add_local_archive(
+        &self,
+        mod_list_store: &ModListStore,
+    ) -> anyhow::Result<(Mod, ModArchive)> {
+        let path = PathBuf::from(&self.source).expand();
+        if !path.is_file() {
+            bail!("No archive found at '{}'", path.display());
+        }
+
+        if !is_supported_archive(&path) {
+            bail!(
+                "Unsupported archive format '{}', expected one of: {}",
+                path.display(),
+                ARCHIVE_EXTENSIONS.join(", ")
+            );
+        }
+
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+
+        mod_list_store.add_local_archive(&name, &path)
+    }
+}
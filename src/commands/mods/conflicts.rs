@@ -0,0 +1,57 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{conflicts, mod_list_store::ModListStore},
+};
+
+#[derive(Args)]
+pub struct Conflicts {
+    /// Only show the per-mod summary, without listing every conflicting file.
+    #[arg(short, long)]
+    pub summary: bool,
+}
+
+impl Conflicts {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace);
+        let report = conflicts::find_conflicts(&mod_list_store.layers()?)?;
+
+        if report.conflicts.is_empty() {
+            println!("{}", "No file conflicts between installed mods.".italic());
+            return Ok(());
+        }
+
+        if !self.summary {
+            println!("\n{}\n", "Conflicting files:".cyan().bold().underline());
+
+            for conflict in &report.conflicts {
+                println!("{}", conflict.path.bold());
+                println!("  {} {}", "wins:".green(), conflict.winner);
+                println!(
+                    "  {} {}",
+                    "loses:".red(),
+                    conflict.losers.join(", ").dimmed()
+                );
+            }
+        }
+
+        println!("\n{}\n", "Summary:".cyan().bold().underline());
+
+        for summary in &report.summaries {
+            println!("{}", summary.label.bold());
+
+            for (label, count) in &summary.overwrites {
+                println!("  overwrites {} file(s) from {}", count.green(), label);
+            }
+            for (label, count) in &summary.overwritten_by {
+                println!("  overwritten by {} file(s) from {}", count.red(), label);
+            }
+        }
+
+        Ok(())
+    }
+}
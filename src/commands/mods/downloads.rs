@@ -1,9 +1,15 @@
 use clap::Args;
+use crossterm::style::Stylize;
 
 use crate::{
     config::Config,
+    daemon::{
+        self, client,
+        protocol::{Job, JobState},
+    },
     games::{Game, workspace::Workspace},
     mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    types::DownloadProgress,
     ui::progress,
 };
 
@@ -16,17 +22,62 @@ impl Downloads {
         let mod_list_store = ModListStore::new(workspace.clone());
         let tracker = DownloadTracker::new(workspace.clone(), mod_list_store);
 
-        let reset = tracker.reset_stuck_downloads()?;
+        // A running daemon owns the downloads it claimed, even before they report progress.
+        let socket_path = daemon::socket_path();
+        if !client::is_running(&socket_path) {
+            let reset = tracker.reset_stuck_downloads()?;
 
-        if reset > 0 {
-            println!(
-                "Info: cancelled {} stuck downloads. If this happens frequently please open an issue in github.",
-                reset
-            );
+            if reset > 0 {
+                println!(
+                    "Info: cancelled {} stuck downloads. If this happens frequently please open an issue in github.",
+                    reset
+                );
+            }
+
+            progress::display_active_downloads(&workspace.tracking_dir())?;
+            return Ok(());
+        }
+
+        let finished: Vec<Job> = client::status(&socket_path)?
+            .into_iter()
+            .filter(|j| !j.is_active())
+            .collect();
+
+        if !finished.is_empty() {
+            println!("\n{}", "Finished downloads:".cyan().bold().underlined());
+            for job in &finished {
+                match &job.state {
+                    JobState::Failed(reason) => {
+                        println!("{} {} ({})", "failed".red(), job.label(), reason)
+                    }
+                    _ => println!("{} {}", "done".green(), job.label()),
+                }
+            }
         }
 
-        progress::display_active_downloads(&workspace.tracking_dir())?;
+        progress::display_downloads(|| {
+            Ok(client::status(&socket_path)?
+                .into_iter()
+                .filter(Job::is_active)
+                .map(|j| (j.id, live_progress(j)))
+                .collect())
+        })
+    }
+}
 
-        Ok(())
+/// Queued jobs don't have a tracking file yet, so they are shown as empty bars.
+fn live_progress(job: Job) -> DownloadProgress {
+    match job.progress {
+        Some(progress) => progress,
+        None => DownloadProgress {
+            file_name: match job.state {
+                JobState::Queued => format!("{} (queued)", job.label()),
+                _ => job.label().to_string(),
+            },
+            progress_bytes: 0,
+            total_bytes: 0,
+            started_at: 0,
+            updated_at: 0,
+        },
     }
 }
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use anyhow::Context;
+use clap::Args;
+use owo_colors::OwoColorize;
+use reqwest::Url;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::url::{self, UrlDownloader},
+    usage_for,
+};
+
+#[derive(Args)]
+pub struct Fetch {
+    /// Direct http(s) link to a zip, 7z or rar archive.
+    pub url: String,
+
+    /// Name to show for the mod, defaults to the archive name without extension.
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Expected SHA-256 of the archive, the download is discarded when it doesn't match.
+    #[arg(long)]
+    pub sha256: Option<String>,
+}
+
+impl Fetch {
+    pub async fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let url = Url::parse(&self.url).with_context(|| format!("Invalid url '{}'", self.url))?;
+        let file_name = url::archive_name(&url)?;
+
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => Path::new(&file_name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+        let downloader = UrlDownloader::new(download_tracker)?;
+
+        let (m, archive) = downloader
+            .fetch(&mod_list_store, &name, &url, self.sha256.as_deref())
+            .await?;
+
+        println!(
+            "{} '{}' as {}, install it with '{}'",
+            "Fetched".green(),
+            archive.file_name,
+            m.name.bold(),
+            usage_for!(Cli::MOD, Cli::MOD_INSTALL)
+        );
+
+        Ok(())
+    }
+}
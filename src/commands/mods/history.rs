@@ -0,0 +1,72 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::mod_list_store::ModListStore,
+    types::{JournalEntry, ModList},
+    ui::print,
+};
+
+#[derive(Args)]
+pub struct History {
+    /// Number of most recent changes to show.
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+impl History {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace);
+        let entries = mod_list_store.history()?;
+
+        if entries.is_empty() {
+            println!("{}", "No changes recorded yet.".italic());
+            return Ok(());
+        }
+
+        let mod_list = mod_list_store.read()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let skip = entries.len().saturating_sub(self.limit);
+
+        println!("\n{}\n", "Mod list history:".cyan().bold().underline());
+
+        for entry in entries.iter().skip(skip) {
+            let undo = entry
+                .reverts
+                .map(|id| format!(" (undo of #{})", id))
+                .unwrap_or_default();
+
+            println!(
+                "#{:<4} {:>9}  {:<9} {}: {} -> {}{}",
+                entry.id,
+                print::age(now.saturating_sub(entry.timestamp)).dimmed(),
+                entry.operation.bold(),
+                label(&mod_list, entry),
+                entry.old_status,
+                entry.new_status,
+                undo.dimmed()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn label(mod_list: &ModList, entry: &JournalEntry) -> String {
+    mod_list
+        .mods
+        .iter()
+        .find(|m| m.uid == entry.mod_uid)
+        .and_then(|m| {
+            m.archives
+                .iter()
+                .find(|a| a.file_uid == entry.file_uid)
+                .map(|a| format!("{} ({})", m.name, a.file_name))
+        })
+        .unwrap_or_else(|| format!("mod {} file {}", entry.mod_uid, entry.file_uid))
+}
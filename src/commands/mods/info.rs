@@ -0,0 +1,96 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::bail;
+use clap::Args;
+use indicatif::HumanBytes;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::mod_list_store::ModListStore,
+    types::{Mod, ModArchive},
+    ui::print,
+};
+
+#[derive(Args)]
+pub struct Info {
+    /// Name, uid or Nexus mod id of the mod.
+    #[arg(value_name = "MOD")]
+    pub mod_query: String,
+}
+
+impl Info {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace);
+
+        let Some(m) = mod_list_store
+            .read()?
+            .mods
+            .into_iter()
+            .find(|m| m.matches(&self.mod_query))
+        else {
+            bail!("No mod matching '{}' found", self.mod_query);
+        };
+
+        print_mod(&m);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        println!("\n{}", "Archives:".cyan().bold().underline());
+        for archive in &m.archives {
+            print_archive(archive, now);
+        }
+
+        Ok(())
+    }
+}
+
+fn print_mod(m: &Mod) {
+    println!(
+        "\n{} {}",
+        m.name.bold(),
+        format!("(uid {})", m.uid).dimmed()
+    );
+
+    println!("{:<10} {}", "Source:", m.source());
+
+    let fields = [
+        ("Mod id:", m.mod_id.map(|id| id.to_string())),
+        ("Author:", m.author.clone()),
+        ("Version:", m.version.clone()),
+        ("Category:", m.category_id.map(|id| id.to_string())),
+        ("Repo:", m.repository.clone()),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("{:<10} {}", label, value);
+        }
+    }
+
+    if let Some(summary) = &m.summary {
+        println!("\n{}", summary.italic());
+    }
+}
+
+fn print_archive(archive: &ModArchive, now: u64) {
+    let details: Vec<String> = [
+        archive.version.as_ref().map(|v| format!("v{}", v)),
+        archive.category.clone(),
+        archive.size.map(|size| HumanBytes(size).to_string()),
+        archive
+            .uploaded_at
+            .map(|uploaded_at| format!("uploaded {}", print::age(now.saturating_sub(uploaded_at)))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    println!(
+        "  {} [{}] {}",
+        archive.file_name.bold(),
+        archive.status,
+        details.join(", ").dimmed()
+    );
+}
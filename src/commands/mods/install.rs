@@ -1,18 +1,35 @@
+use anyhow::bail;
 use clap::Args;
 use crossterm::style::Stylize;
 
 use crate::{
     config::Config,
     games::{Game, workspace::Workspace},
-    mods::mod_list_store::ModListStore,
+    mods::{
+        fomod::{Chooser, DefaultChooser, PromptChooser},
+        mod_list_store::ModListStore,
+    },
     types::{FileStatus, Mod},
     ui::prompt,
 };
 
 #[derive(Args)]
 pub struct Install {
-    #[arg(long)]
+    /// Installs every downloaded archive without prompting, using the default FOMOD options.
+    #[arg(long, conflicts_with_all = ["mod_query", "file"])]
     pub all: bool,
+
+    /// Only installs archives of the mod with this uid or name.
+    #[arg(long = "mod", value_name = "UID|NAME")]
+    pub mod_query: Option<String>,
+
+    /// Only installs the archives with these file uids.
+    #[arg(long, value_name = "FILE_UID")]
+    pub file: Vec<u64>,
+
+    /// Skips confirmation and uses the default FOMOD options.
+    #[arg(short, long)]
+    pub yes: bool,
 }
 
 impl Install {
@@ -35,7 +52,21 @@ impl Install {
             return Ok(());
         }
 
-        for mod_entry in mods_with_downloaded_archives {
+        let non_interactive = self.non_interactive();
+
+        let mods_to_install = if self.selecting() {
+            let selected = self.select(mods_with_downloaded_archives);
+            if selected.is_empty() {
+                bail!("No downloaded archives match the given selection.");
+            }
+            selected
+        } else {
+            mods_with_downloaded_archives
+        };
+
+        let mut failures = Vec::new();
+
+        for mod_entry in mods_to_install {
             let name = mod_entry.name.to_string();
             println!(
                 "\n{}: '{}'",
@@ -44,7 +75,7 @@ impl Install {
             );
 
             let mut archives_to_install = Vec::new();
-            if mod_entry.archives.len() > 1 {
+            if self.prompts_for_archives(&mod_entry) {
                 let selection = prompt::select_multiple(
                     "Select one or more archives to install.",
                     &mod_entry.archives,
@@ -65,20 +96,118 @@ impl Install {
                     .join("\n")
             );
 
-            println!();
-            let confirmed = prompt::confirm("Do you want to install?")?;
+            if !non_interactive {
+                println!();
+                let confirmed = prompt::confirm("Do you want to install?")?;
 
-            if !confirmed {
-                continue;
+                if !confirmed {
+                    continue;
+                }
             }
 
+            let chooser: &mut dyn Chooser = if non_interactive {
+                &mut DefaultChooser
+            } else {
+                &mut PromptChooser
+            };
+
             for archive in archives_to_install {
-                mod_list_store.install_archive(&mod_entry, &archive)?;
+                match mod_list_store.install_archive(&mod_entry, &archive, chooser) {
+                    Ok(normalized) => {
+                        for (from, to) in normalized.renamed {
+                            println!("Renamed '{}' to '{}'", from.display(), to.display());
+                        }
+                        for path in normalized.collisions {
+                            println!(
+                                "{} '{}', a folder with a different casing already has it",
+                                "Kept".yellow(),
+                                path.display()
+                            );
+                        }
 
-                println!("Successfully installed '{}'", archive.file_name);
+                        println!("Successfully installed '{}'", archive.file_name);
+                    }
+                    Err(e) => {
+                        println!(
+                            "{} '{}': {}",
+                            "Failed to install".red(),
+                            archive.file_name,
+                            e
+                        );
+                        failures.push((mod_entry.name.clone(), archive, e));
+                    }
+                }
             }
         }
 
+        if !failures.is_empty() {
+            println!(
+                "\n{}",
+                "The following archives failed to install:".red().bold()
+            );
+            for (mod_name, archive, e) in &failures {
+                println!("  {} ({}): {}", archive.file_name, mod_name, e);
+            }
+
+            bail!("{} archive(s) failed to install", failures.len());
+        }
+
+        Ok(())
+    }
+
+    fn non_interactive(&self) -> bool {
+        self.all || self.yes
+    }
+
+    fn selecting(&self) -> bool {
+        self.all || self.mod_query.is_some() || !self.file.is_empty()
+    }
+
+    /// Whether to ask which of the mod's archives to install, instead of installing all of them.
+    fn prompts_for_archives(&self, m: &Mod) -> bool {
+        m.archives.len() > 1 && !self.selecting() && !self.non_interactive()
+    }
+
+    fn select(&self, mods: Vec<Mod>) -> Vec<Mod> {
+        mods.into_iter()
+            .filter(|m| self.mod_query.as_ref().is_none_or(|q| m.matches(q)))
+            .filter_map(|mut m| {
+                m.archives
+                    .retain(|a| self.file.is_empty() || self.file.contains(&a.file_uid));
+                if m.archives.is_empty() { None } else { Some(m) }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ModArchive;
+
+    fn install(yes: bool, file: Vec<u64>) -> Install {
+        Install {
+            all: false,
+            mod_query: None,
+            file,
+            yes,
+        }
+    }
+
+    #[test]
+    fn prompts_for_archives_should_only_ask_when_interactive_and_not_selecting()
+    -> anyhow::Result<()> {
+        // Arrange
+        let m = Mod {
+            archives: vec![ModArchive::default(), ModArchive::default()],
+            ..Default::default()
+        };
+
+        // Act & Assert
+        assert!(install(false, vec![]).prompts_for_archives(&m));
+        assert!(!install(true, vec![]).prompts_for_archives(&m));
+        assert!(!install(false, vec![1]).prompts_for_archives(&m));
+
         Ok(())
     }
 }
@@ -1,4 +1,13 @@
+pub mod add;
+pub mod conflicts;
 pub mod downloads;
+pub mod fetch;
+pub mod history;
+pub mod info;
 pub mod install;
 pub mod nxm;
+pub mod order;
+pub mod retry;
+pub mod undo;
 pub mod uninstall;
+pub mod updates;
@@ -1,134 +1,68 @@
-use anyhow::{Context, bail};
+use std::{
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
 use clap::Args;
 
 use crate::{
-    config::Config,
-    games::workspace::Workspace,
-    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
-    sources::nexus::{self},
-    types::FileStatus,
+    cli::Cli,
+    daemon::{self, client},
+    sources::nexus,
     ui::notify,
-    usage_for,
 };
 
+const DAEMON_STARTUP_TIMEOUT: Duration = Duration::from_secs(3);
+
 #[derive(Args)]
 pub struct NxmHandler {
     pub url: String,
 }
 
 impl NxmHandler {
-    pub async fn run(&self, config: &Config) -> anyhow::Result<()> {
-        let nexus_config = match nexus::Config::load() {
-            Ok(config) => config,
-            Err(_) => {
-                let message = &format!(
-                    "Nexus connection is not set up. Run '{}'",
-                    usage_for!("connect")
-                );
-                notify::send_notification(message)?;
-                bail!("{message}");
-            }
-        };
-
-        let parsed = nexus::parse_nxm_url(&self.url)?;
-
-        let domain = &parsed.game;
-        let game = nexus::from_nexus_domain(domain)?;
-
-        let workspace = Workspace::new(&game, config)?;
-        let mod_list_store = ModListStore::new(workspace.clone());
-        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
-
-        let api = nexus::Nexus::new(&nexus_config, download_tracker.clone())?;
-
-        let mod_info = api.get_mod_info(&game, &parsed.mod_id).await?;
-        let file_info_result = api
-            .get_mod_file_info(&game, &parsed.mod_id, &parsed.file_id)
-            .await;
-        let mut file_info = match file_info_result {
-            Ok(i) => i,
-            Err(err) => {
-                notify::send_notification(&format!(
-                    "A problem occured during download: '{}'",
-                    err
-                ))?;
-                bail!(err)
-            }
-        };
+    /// Hands the link to the download daemon, starting one if none is running.
+    pub fn run(&self) -> anyhow::Result<()> {
+        nexus::parse_nxm_url(&self.url)?;
 
-        let current_archive_status =
-            mod_list_store.get_archive_status(mod_info.uid, file_info.file_uid)?;
-
-        if current_archive_status != FileStatus::Unknown {
-            match &current_archive_status {
-                FileStatus::Downloading => {
-                    notify::send_notification(&format!(
-                        "There is already a download in progress for '{}'.",
-                        file_info.file_name
-                    ))?;
-                    return Ok(());
-                }
-                FileStatus::Downloaded => {
-                    notify::send_notification(&format!(
-                        "'{}' is already downloaded.",
-                        file_info.file_name
-                    ))?;
-                    return Ok(());
-                }
-                FileStatus::Installed => {
-                    notify::send_notification(&format!(
-                        "'{}' is already installed.",
-                        file_info.file_name
-                    ))?;
-                    return Ok(());
-                }
-                FileStatus::Unknown | FileStatus::Failed(_) => {
-                    // TODO: Allow redownload for now, add prompt or something later
-                }
-            };
+        let socket_path = daemon::socket_path();
+        if !client::is_running(&socket_path) {
+            start_daemon(&socket_path)?;
         }
-        let archive_path = mod_list_store.archive_download_dest(&file_info.file_name);
-
-        file_info.status = FileStatus::Downloading;
-        file_info.archive_path = Some(archive_path.clone());
-        mod_list_store.add_archive(&mod_info, file_info.clone())?;
-
-        notify::send_notification(&format!("Starting download for '{}'", file_info.file_name))?;
-
-        let tracking_file = download_tracker
-            .ensure_tracking_file(file_info.file_uid)
-            .with_context(|| {
-                format!(
-                    "Could not resolve tracking file path for {}",
-                    file_info.file_name
-                )
-            })?;
-
-        let download_link = api.get_download_link(&parsed).await?;
-        let download_result = api
-            .download_file(&download_link, &archive_path, &tracking_file)
-            .await;
 
-        match download_result {
-            Ok(()) => {
-                notify::send_notification(&format!(
-                    "Download complete for '{}'",
-                    file_info.file_name
-                ))?;
-                mod_list_store.stage_archive(&mod_info, &file_info)?;
-            }
+        match client::enqueue(&socket_path, &self.url) {
+            Ok(_) => Ok(()),
             Err(err) => {
-                notify::send_notification(&format!(
-                    "Download failed for '{}': {}",
-                    file_info.file_name, err
-                ))?;
-                mod_list_store.update_archive(&mod_info.uid, &file_info.file_uid, |a| {
-                    a.status = FileStatus::Failed(format!("{err:#}"))
-                })?;
-                return Err(err);
+                notify::send_notification(&format!("Could not queue download: {}", err))?;
+                Err(err)
             }
         }
+    }
+}
 
-        Ok(())
+fn start_daemon(socket_path: &Path) -> anyhow::Result<()> {
+    // Own process group, so the daemon outlives the browser that opened the link.
+    Command::new(std::env::current_exe()?)
+        .arg(Cli::DAEMON)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+
+    let started = Instant::now();
+    while !client::is_running(socket_path) {
+        if started.elapsed() > DAEMON_STARTUP_TIMEOUT {
+            bail!(
+                "Download daemon did not start listening on '{}'",
+                socket_path.display()
+            );
+        }
+        thread::sleep(Duration::from_millis(50));
     }
+
+    Ok(())
 }
@@ -0,0 +1,39 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::mod_list_store::ModListStore,
+    ui::reorder,
+};
+
+#[derive(Args)]
+pub struct Order;
+
+impl Order {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace);
+        let layers = mod_list_store.layers()?;
+
+        if layers.is_empty() {
+            println!("{}", "No installed mods found.".italic());
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            "\nPlease order your mods, mods lower in the list win file conflicts:"
+                .bold()
+                .cyan()
+        );
+
+        let layers = reorder::reorder_items(layers)?;
+        mod_list_store.set_priority(&layers)?;
+
+        println!("{}", "Mod order saved.".bold());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, bail};
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::{Source, github::GitHub, nexus, url::UrlDownloader},
+    types::{FileStatus, Mod, ModArchive},
+    ui::prompt,
+    usage_for,
+};
+
+#[derive(Args)]
+pub struct Retry {
+    /// Retries every failed download without prompting.
+    #[arg(long)]
+    pub all: bool,
+}
+
+impl Retry {
+    pub async fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+
+        let failed: Vec<(Mod, ModArchive)> = mod_list_store
+            .read()?
+            .mods
+            .into_iter()
+            // Local archives have nowhere to be downloaded from again.
+            .filter(|m| m.source() != Source::Local)
+            .flat_map(|m| {
+                m.archives
+                    .iter()
+                    .filter(|a| matches!(a.status, FileStatus::Failed(_)))
+                    .map(|a| (m.clone(), a.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        if failed.is_empty() {
+            println!("{}", "No failed downloads found.".italic());
+            return Ok(());
+        }
+
+        let selected = if self.all {
+            failed
+        } else {
+            let labels: Vec<String> = failed
+                .iter()
+                .map(|(m, a)| match &a.status {
+                    FileStatus::Failed(reason) => {
+                        format!("{} - {} ({})", m.name, a.file_name, reason)
+                    }
+                    _ => format!("{} - {}", m.name, a.file_name),
+                })
+                .collect();
+
+            prompt::select_multiple_indices(
+                "Select the downloads to retry.",
+                &labels,
+                &vec![true; labels.len()],
+                false,
+            )?
+            .into_iter()
+            .map(|i| failed[i].clone())
+            .collect()
+        };
+
+        let nexus = if selected.iter().any(|(m, _)| m.source() == Source::Nexus) {
+            let nexus_config = match nexus::Config::load() {
+                Ok(config) => config,
+                Err(_) => bail!(
+                    "Nexus connection is not set up. Run '{}'",
+                    usage_for!("connect")
+                ),
+            };
+            Some(nexus::Nexus::new(&nexus_config, download_tracker.clone())?)
+        } else {
+            None
+        };
+        let url_downloader = UrlDownloader::new(download_tracker.clone())?;
+        let github = GitHub::new(download_tracker)?;
+
+        let mut failures = 0;
+        for (m, archive) in &selected {
+            println!("Retrying '{}'...", archive.file_name.bold());
+
+            let result = match m.source() {
+                Source::Url => url_downloader
+                    .download_archive(&mod_list_store, m, archive.clone())
+                    .await
+                    .map(|_| ()),
+                Source::GitHub => github
+                    .download_archive(&mod_list_store, m, archive.clone())
+                    .await
+                    .map(|_| ()),
+                _ => {
+                    async {
+                        let api = nexus.as_ref().context("Nexus connection is not set up")?;
+                        let url = api
+                            .get_premium_download_link(current_game, m.uid, archive.file_uid)
+                            .await?;
+                        api.download_archive(&mod_list_store, m, archive, &url)
+                            .await
+                    }
+                    .await
+                }
+            };
+
+            match result {
+                Ok(()) => println!("Downloaded '{}'", archive.file_name),
+                Err(e) => {
+                    failures += 1;
+                    println!(
+                        "{} '{}': {}",
+                        "Retry failed for".red(),
+                        archive.file_name,
+                        e
+                    );
+                }
+            }
+        }
+
+        if failures > 0 {
+            println!(
+                "\n{}",
+                "Without a premium account Nexus requires a fresh download, use 'Mod Manager Download' on the mod page instead."
+                    .dimmed()
+            );
+            bail!("{} of {} download(s) failed", failures, selected.len());
+        }
+
+        Ok(())
+    }
+}
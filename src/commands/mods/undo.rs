@@ -0,0 +1,58 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{
+        fomod::{Chooser, DefaultChooser, PromptChooser},
+        mod_list_store::ModListStore,
+    },
+    types::Operation,
+};
+
+#[derive(Args)]
+pub struct Undo {
+    /// Uses the default FOMOD options when an undo has to install an archive again.
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+impl Undo {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace);
+
+        let chooser: &mut dyn Chooser = if self.yes {
+            &mut DefaultChooser
+        } else {
+            &mut PromptChooser
+        };
+
+        match mod_list_store.undo(chooser)? {
+            Some(entry) => {
+                let action = match entry.operation {
+                    Operation::Install => "Uninstalled",
+                    _ => "Reinstalled",
+                };
+                let file_name = mod_list_store
+                    .read()?
+                    .mods
+                    .into_iter()
+                    .flat_map(|m| m.archives)
+                    .find(|a| a.file_uid == entry.file_uid)
+                    .map_or_else(|| entry.file_uid.to_string(), |a| a.file_name);
+
+                println!(
+                    "{} '{}', undoing change #{}",
+                    action.green(),
+                    file_name,
+                    entry.id
+                );
+            }
+            None => println!("{}", "Nothing to undo.".italic()),
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,81 @@
 use clap::Args;
+use crossterm::style::Stylize;
 
-use crate::{config::Config, games::Game};
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::mod_list_store::ModListStore,
+    types::{FileStatus, Mod},
+    ui::prompt,
+};
 
 #[derive(Args)]
-pub struct Uninstall;
+pub struct Uninstall {
+    /// Removes the archive from the mod list instead of marking it as downloaded again.
+    #[arg(long)]
+    pub remove: bool,
+
+    /// Deletes the staged archive as well, this implies `--remove`.
+    #[arg(long)]
+    pub delete_archive: bool,
+}
 
 impl Uninstall {
-    pub fn run(&self, _config: &Config, _current_game: &Game) -> anyhow::Result<()> {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace);
+        let mod_list = mod_list_store.read()?;
+
+        let mods_with_installed_archives: Vec<Mod> = mod_list
+            .mods
+            .into_iter()
+            .filter_map(|mut m| {
+                m.archives.retain(|a| a.status == FileStatus::Installed);
+                if m.archives.is_empty() { None } else { Some(m) }
+            })
+            .collect();
+
+        if mods_with_installed_archives.is_empty() {
+            println!("{}", "No installed mods found.".italic());
+            return Ok(());
+        }
+
+        let mod_entry = prompt::select("Select a mod to uninstall", &mods_with_installed_archives)?;
+
+        let archives_to_uninstall = if mod_entry.archives.len() > 1 {
+            prompt::select_multiple(
+                "Select one or more archives to uninstall.",
+                &mod_entry.archives,
+            )?
+        } else {
+            mod_entry.archives.clone()
+        };
+
+        println!(
+            "About to uninstall the following archives: \n\n{}",
+            archives_to_uninstall
+                .iter()
+                .enumerate()
+                .map(|(i, a)| format!("{}. {}", i + 1, a.file_name))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+
+        println!();
+        if !prompt::confirm("Do you want to uninstall?")? {
+            return Ok(());
+        }
+
+        for archive in archives_to_uninstall {
+            mod_list_store.uninstall_archive(&mod_entry, &archive)?;
+
+            if self.remove || self.delete_archive {
+                mod_list_store.remove_archive(&mod_entry, &archive, self.delete_archive)?;
+            }
+
+            println!("Successfully uninstalled '{}'", archive.file_name);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,102 @@
+use anyhow::bail;
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::{
+        Source,
+        github::GitHub,
+        nexus::{self, ArchiveUpdate},
+    },
+    usage_for,
+};
+
+#[derive(Args)]
+pub struct Updates {}
+
+impl Updates {
+    pub async fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+
+        let mods = mod_list_store.read()?.mods;
+        let has_source = |source: Source| mods.iter().any(|m| m.source() == source);
+        let (check_nexus, check_github) = (has_source(Source::Nexus), has_source(Source::GitHub));
+        if !check_nexus && !check_github {
+            println!("{}", "No Nexus or GitHub mods to check.".italic());
+            return Ok(());
+        }
+
+        let mut updates = Vec::new();
+
+        if check_nexus {
+            let nexus_config = match nexus::Config::load() {
+                Ok(config) => config,
+                Err(_) => bail!(
+                    "Nexus connection is not set up. Run '{}'",
+                    usage_for!("connect")
+                ),
+            };
+            let api = nexus::Nexus::new(&nexus_config, download_tracker.clone())?;
+
+            println!("Checking Nexus for updates...");
+            updates.extend(api.check_updates(current_game, &mods).await?);
+        }
+
+        if check_github {
+            println!("Checking GitHub for updates...");
+            updates.extend(GitHub::new(download_tracker)?.check_updates(&mods).await?);
+        }
+
+        if updates.is_empty() {
+            println!("{}", "All mods are up to date.".green());
+            return Ok(());
+        }
+
+        println!("\n{}\n", "Available updates:".cyan().bold().underline());
+        for update in &updates {
+            print_update(update);
+        }
+
+        Ok(())
+    }
+}
+
+fn print_update(update: &ArchiveUpdate) {
+    println!(
+        "{} ({}) {}",
+        update.mod_name.bold(),
+        update.file_name,
+        update
+            .version
+            .as_deref()
+            .unwrap_or("unknown version")
+            .dimmed()
+    );
+
+    if let Some(category) = &update.outdated_category {
+        println!(
+            "  {} marked as {} by the author",
+            "!".yellow(),
+            category.yellow()
+        );
+    }
+
+    match &update.newer_file {
+        Some(newer) => {
+            println!(
+                "  -> {} {}",
+                newer.file_name.green(),
+                newer.version.as_deref().unwrap_or_default().green()
+            );
+            if let Some(changelog) = &newer.changelog {
+                println!("     {}", changelog.dimmed());
+            }
+        }
+        None => println!("  {}", "No replacement file linked on Nexus.".dimmed()),
+    }
+}
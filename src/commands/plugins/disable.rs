@@ -0,0 +1,15 @@
+use clap::Args;
+
+use crate::{config::Config, games::Game};
+
+#[derive(Args)]
+pub struct Disable {
+    /// Plugin file names to disable, prompts for a selection when none given.
+    pub plugins: Vec<String>,
+}
+
+impl Disable {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        super::set_enabled(config, current_game, &self.plugins, false)
+    }
+}
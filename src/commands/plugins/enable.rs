@@ -0,0 +1,15 @@
+use clap::Args;
+
+use crate::{config::Config, games::Game};
+
+#[derive(Args)]
+pub struct Enable {
+    /// Plugin file names to enable, prompts for a selection when none given.
+    pub plugins: Vec<String>,
+}
+
+impl Enable {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        super::set_enabled(config, current_game, &self.plugins, true)
+    }
+}
@@ -0,0 +1,40 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::plugin_list_store::{self, PluginListStore},
+};
+
+#[derive(Args)]
+pub struct List;
+
+impl List {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let plugin_list_store = PluginListStore::new(workspace);
+        let plugin_list = plugin_list_store.sync()?;
+
+        if plugin_list.plugins.is_empty() {
+            println!("{}", "No plugins installed.".italic());
+            return Ok(());
+        }
+
+        println!("\n{}\n", "Load order:".cyan().bold().underline());
+
+        for (i, plugin) in plugin_list_store::load_order(&plugin_list)
+            .into_iter()
+            .enumerate()
+        {
+            let line = format!("{:>3}. {}", i + 1, plugin.name);
+            if plugin.enabled {
+                println!("{}", line);
+            } else {
+                println!("{} {}", line.dimmed(), "(disabled)".dimmed());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::plugin_list_store::PluginListStore,
+    types::Plugin,
+    ui::prompt,
+};
+
+pub mod disable;
+pub mod enable;
+pub mod list;
+pub mod reorder;
+
+/// Enables or disables the given plugins, prompts for a selection of the plugins that aren't
+/// in that state yet when none are given.
+fn set_enabled(
+    config: &Config,
+    current_game: &Game,
+    plugins: &[String],
+    enabled: bool,
+) -> anyhow::Result<()> {
+    let action = if enabled { "enable" } else { "disable" };
+    let workspace = Workspace::new(current_game, config)?;
+    let plugin_list_store = PluginListStore::new(workspace);
+    let plugin_list = plugin_list_store.sync()?;
+
+    let names = if plugins.is_empty() {
+        let candidates: Vec<Plugin> = plugin_list
+            .plugins
+            .into_iter()
+            .filter(|p| p.enabled != enabled)
+            .collect();
+
+        if candidates.is_empty() {
+            println!(
+                "{}",
+                format!("All plugins are already {}d.", action).italic()
+            );
+            return Ok(());
+        }
+
+        prompt::select_multiple(
+            &format!("Select one or more plugins to {}.", action),
+            &candidates,
+        )?
+        .into_iter()
+        .map(|p| p.name)
+        .collect()
+    } else {
+        plugins.to_vec()
+    };
+
+    plugin_list_store.set_enabled(&names, enabled)?;
+
+    let done = if enabled { "Enabled" } else { "Disabled" };
+    for name in names {
+        println!("{} '{}'", done, name.bold());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::plugin_list_store::PluginListStore,
+    ui::reorder,
+};
+
+#[derive(Args)]
+pub struct Reorder;
+
+impl Reorder {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let plugin_list_store = PluginListStore::new(workspace);
+        let mut plugin_list = plugin_list_store.sync()?;
+
+        if plugin_list.plugins.is_empty() {
+            println!("{}", "No plugins installed.".italic());
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            "\nPlease order your plugins, plugins lower in the list load later:"
+                .bold()
+                .cyan()
+        );
+
+        plugin_list.plugins = reorder::reorder_items(plugin_list.plugins)?;
+        plugin_list_store.write(&plugin_list)?;
+
+        println!("{}", "Load order saved.".bold());
+
+        Ok(())
+    }
+}
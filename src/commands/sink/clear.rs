@@ -0,0 +1,36 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::sink_store::SinkStore,
+    ui::prompt,
+};
+
+#[derive(Args)]
+pub struct Clear {
+    /// Skips the confirmation prompt.
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+impl Clear {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let sink_store = SinkStore::new(workspace);
+
+        if !self.yes && !prompt::confirm("This deletes everything in the sink, continue?")? {
+            return Ok(());
+        }
+
+        let removed = sink_store.clear()?;
+        if removed == 0 {
+            println!("{}", "Sink is already empty.".italic());
+        } else {
+            println!("{}", "Sink cleared.".bold());
+        }
+
+        Ok(())
+    }
+}
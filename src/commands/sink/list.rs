@@ -0,0 +1,43 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::sink_store::SinkStore,
+};
+
+#[derive(Args)]
+pub struct List;
+
+impl List {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let sink_store = SinkStore::new(workspace);
+        let entries = sink_store.entries()?;
+
+        if entries.is_empty() {
+            println!("{}", "Sink is empty.".italic());
+            return Ok(());
+        }
+
+        println!("\n{}\n", "Files in sink:".cyan().bold().underline());
+
+        for entry in &entries {
+            if entry.is_whiteout {
+                println!("{} {}", entry.path.display(), "(deleted)".red());
+            } else {
+                println!(
+                    "{} {}",
+                    entry.path.display(),
+                    format!("({} bytes)", entry.size).dimmed()
+                );
+            }
+        }
+
+        let total: u64 = entries.iter().map(|e| e.size).sum();
+        println!("\n{} file(s), {} bytes in total.", entries.len(), total);
+
+        Ok(())
+    }
+}
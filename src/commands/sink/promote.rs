@@ -0,0 +1,30 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::sink_store::SinkStore,
+};
+
+#[derive(Args)]
+pub struct Promote {
+    /// Name of the mod to create from the sink contents
+    pub name: String,
+}
+
+impl Promote {
+    pub fn run(&self, config: &Config, current_game: &Game) -> anyhow::Result<()> {
+        let workspace = Workspace::new(current_game, config)?;
+        let sink_store = SinkStore::new(workspace);
+
+        let layer = sink_store.promote(&self.name)?;
+
+        println!(
+            "Moved sink into new mod '{}', it now has the highest priority.",
+            layer.label.bold()
+        );
+
+        Ok(())
+    }
+}
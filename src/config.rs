@@ -19,7 +19,7 @@ use crate::{
 pub const CACHE_DIR_NAME: &str = ".cache";
 pub const MODS_DIR_NAME: &str = "mods";
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
     games: HashMap<String, GameConfig>,
     work_dir: PathBuf,
@@ -0,0 +1,58 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::bail;
+
+use crate::daemon::protocol::{Job, Request, Response};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether a daemon is accepting connections on the socket.
+pub fn is_running(socket_path: &Path) -> bool {
+    UnixStream::connect(socket_path).is_ok()
+}
+
+/// Hands a download link to the daemon, returning the id of the queued job.
+pub fn enqueue(socket_path: &Path, url: &str) -> anyhow::Result<u64> {
+    match request(
+        socket_path,
+        &Request::Enqueue {
+            url: url.to_string(),
+        },
+    )? {
+        Response::Queued { id } => Ok(id),
+        Response::Error { message } => bail!("Daemon refused the download: {}", message),
+        _ => bail!("Daemon sent an unexpected response"),
+    }
+}
+
+/// Returns every job the daemon knows about, including finished ones.
+pub fn status(socket_path: &Path) -> anyhow::Result<Vec<Job>> {
+    match request(socket_path, &Request::Status)? {
+        Response::Status { jobs } => Ok(jobs),
+        Response::Error { message } => bail!("Daemon refused the status request: {}", message),
+        _ => bail!("Daemon sent an unexpected response"),
+    }
+}
+
+fn request(socket_path: &Path, request: &Request) -> anyhow::Result<Response> {
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut line = serde_json::to_vec(request)?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    if response.is_empty() {
+        bail!("Daemon closed the connection without responding");
+    }
+
+    Ok(serde_json::from_str(&response)?)
+}
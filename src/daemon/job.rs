@@ -0,0 +1,169 @@
+use anyhow::bail;
+
+use crate::{
+    config::Config,
+    daemon::server::JobReporter,
+    games::workspace::Workspace,
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::nexus::{self, NexusError},
+    types::FileStatus,
+    ui::notify,
+    usage_for,
+};
+
+/// Resolves an nxm link with Nexus and downloads the archive it points to.
+pub async fn download_nxm_link(
+    config: &Config,
+    url: &str,
+    reporter: &JobReporter,
+) -> anyhow::Result<()> {
+    let nexus_config = match nexus::Config::load() {
+        Ok(config) => config,
+        Err(_) => {
+            let message = &format!(
+                "Nexus connection is not set up. Run '{}'",
+                usage_for!("connect")
+            );
+            notify::send_notification(message)?;
+            bail!("{message}");
+        }
+    };
+
+    let parsed = nexus::parse_nxm_url(url)?;
+
+    let domain = &parsed.game;
+    let game = nexus::from_nexus_domain(domain)?;
+
+    let workspace = Workspace::new(&game, config)?;
+    let mod_list_store = ModListStore::new(workspace.clone());
+    let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+
+    let api = nexus::Nexus::new(&nexus_config, download_tracker.clone())?;
+
+    let info = async {
+        let mod_info = api.get_mod_info(&game, &parsed.mod_id).await?;
+        let file_info = api
+            .get_mod_file_info(&game, &parsed.mod_id, &parsed.file_id)
+            .await?;
+        anyhow::Ok((mod_info, file_info))
+    }
+    .await;
+    let (mod_info, file_info) = match info {
+        Ok(info) => info,
+        Err(err) => {
+            notify::send_notification(&failure_message("Could not look up the download", &err))?;
+            return Err(err);
+        }
+    };
+
+    reporter.resolved(
+        &file_info.file_name,
+        download_tracker.tracking_file(file_info.file_uid),
+    );
+
+    let previous_status = mod_list_store.claim_download(&mod_info, &file_info)?;
+
+    match &previous_status {
+        FileStatus::Downloading => {
+            notify::send_notification(&format!(
+                "There is already a download in progress for '{}'.",
+                file_info.file_name
+            ))?;
+            return Ok(());
+        }
+        FileStatus::Downloaded => {
+            notify::send_notification(&format!(
+                "'{}' is already downloaded.",
+                file_info.file_name
+            ))?;
+            return Ok(());
+        }
+        FileStatus::Installed => {
+            notify::send_notification(&format!("'{}' is already installed.", file_info.file_name))?;
+            return Ok(());
+        }
+        FileStatus::Unknown | FileStatus::Failed(_) => {
+            // Failed downloads are started again, resuming where possible.
+        }
+    };
+
+    notify::send_notification(&format!("Starting download for '{}'", file_info.file_name))?;
+
+    let result = match api.get_download_link(&parsed).await {
+        Ok(download_link) => {
+            api.download_claimed_archive(&mod_list_store, &mod_info, &file_info, &download_link)
+                .await
+        }
+        Err(err) => {
+            // The claim has to be released, otherwise the archive looks like it's downloading.
+            mod_list_store.update_archive(&mod_info.uid, &file_info.file_uid, |a| {
+                a.status = FileStatus::Failed(format!("{err:#}"))
+            })?;
+            Err(err)
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            notify::send_notification(&format!("Download complete for '{}'", file_info.file_name))?
+        }
+        Err(err) => {
+            notify::send_notification(&failure_message(
+                &format!("Download failed for '{}'", file_info.file_name),
+                &err,
+            ))?;
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Notification text for a failed step. When Nexus refused, says what to do about it instead of
+/// repeating the raw response.
+fn failure_message(subject: &str, err: &anyhow::Error) -> String {
+    match err.downcast_ref::<NexusError>() {
+        Some(nexus_error) => format!("{}: {}", subject, nexus_error.hint()),
+        None => format!("{}: {}", subject, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_message_should_use_hint_when_nexus_refused() -> anyhow::Result<()> {
+        // Arrange
+        let err = anyhow::Error::from(NexusError::Expired("Link expired".to_string()))
+            .context("Could not get download link");
+
+        // Act
+        let actual = failure_message("Download failed for 'mod.7z'", &err);
+
+        // Assert
+        assert_eq!(
+            actual,
+            format!(
+                "Download failed for 'mod.7z': {}",
+                NexusError::Expired(String::new()).hint()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn failure_message_should_use_error_when_not_from_nexus() -> anyhow::Result<()> {
+        // Arrange
+        let err = anyhow::anyhow!("checksum mismatch");
+
+        // Act
+        let actual = failure_message("Download failed for 'mod.7z'", &err);
+
+        // Assert
+        assert_eq!(actual, "Download failed for 'mod.7z': checksum mismatch");
+
+        Ok(())
+    }
+}
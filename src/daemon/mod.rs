@@ -0,0 +1,22 @@
+pub mod client;
+pub mod job;
+pub mod protocol;
+pub mod server;
+
+use std::path::PathBuf;
+
+/// How many downloads the daemon runs at the same time unless told otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 3;
+
+const SOCKET_NAME: &str = "moma.sock";
+
+/// Unix socket the daemon listens on, inside the user's runtime directory when there is one.
+pub fn socket_path() -> PathBuf {
+    match dirs_next::runtime_dir() {
+        Some(dir) => dir.join(SOCKET_NAME),
+        None => {
+            let uid = unsafe { libc::getuid() };
+            std::env::temp_dir().join(format!("moma-{}.sock", uid))
+        }
+    }
+}
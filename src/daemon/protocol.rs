@@ -0,0 +1,116 @@
+use anyhow::bail;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::types::DownloadProgress;
+
+/// Messages are exchanged as one JSON object per line, one request and one response per connection.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    Enqueue { url: String },
+    Status,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Queued { id: u64 },
+    Status { jobs: Vec<Job> },
+    Error { message: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Downloading,
+    Done,
+    Failed(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Job {
+    pub id: u64,
+    pub url: String,
+    /// Known once the link has been resolved with the mod source.
+    pub file_name: Option<String>,
+    pub state: JobState,
+    /// Bytes received so far, only present while downloading.
+    pub progress: Option<DownloadProgress>,
+}
+
+impl Job {
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, JobState::Queued | JobState::Downloading)
+    }
+
+    pub fn label(&self) -> &str {
+        self.file_name.as_deref().unwrap_or(&self.url)
+    }
+}
+
+pub async fn send<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+
+    Ok(())
+}
+
+pub async fn receive<R: AsyncBufRead + Unpin, T: DeserializeOwned>(
+    reader: &mut R,
+) -> anyhow::Result<T> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        bail!("Connection closed before a message was received");
+    }
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn receive_should_return_sent_message() -> anyhow::Result<()> {
+        // Arrange
+        let (client, server) = tokio::io::duplex(1024);
+        let mut writer = client;
+        let mut reader = BufReader::new(server);
+        let request = Request::Enqueue {
+            url: "nxm://skyrimspecialedition/mods/1/files/2".to_string(),
+        };
+
+        // Act
+        send(&mut writer, &request).await?;
+        let actual: Request = receive(&mut reader).await?;
+
+        // Assert
+        assert_eq!(actual, request);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn receive_should_return_err_when_connection_closed() -> anyhow::Result<()> {
+        // Arrange
+        let (client, server) = tokio::io::duplex(1024);
+        drop(client);
+        let mut reader = BufReader::new(server);
+
+        // Act
+        let result: anyhow::Result<Request> = receive(&mut reader).await;
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    cell::{Cell, RefCell},
+    fs,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    rc::Rc,
+};
+
+use tokio::{
+    io::BufReader,
+    net::{UnixListener, UnixStream},
+    sync::Semaphore,
+};
+
+use crate::{
+    daemon::protocol::{self, Job, JobState, Request, Response},
+    types::DownloadProgress,
+};
+
+/// Runs a single queued link to completion.
+pub type JobRunner =
+    Rc<dyn Fn(String, JobReporter) -> Pin<Box<dyn Future<Output = anyhow::Result<()>>>>>;
+
+struct JobEntry {
+    job: Job,
+    tracking_file: Option<PathBuf>,
+}
+
+/// Lets a running job tell the daemon what it is downloading, so status requests can show progress.
+#[derive(Clone)]
+pub struct JobReporter {
+    id: u64,
+    jobs: Rc<RefCell<Vec<JobEntry>>>,
+}
+
+impl JobReporter {
+    pub fn resolved(&self, file_name: &str, tracking_file: PathBuf) {
+        if let Some(entry) = self
+            .jobs
+            .borrow_mut()
+            .iter_mut()
+            .find(|e| e.job.id == self.id)
+        {
+            entry.job.file_name = Some(file_name.to_string());
+            entry.tracking_file = Some(tracking_file);
+        }
+    }
+}
+
+/// Owns the download queue. Jobs and connections are spawned on the current `LocalSet`,
+/// so the server must be run inside one.
+pub struct Server {
+    jobs: Rc<RefCell<Vec<JobEntry>>>,
+    permits: Rc<Semaphore>,
+    runner: JobRunner,
+    next_id: Cell<u64>,
+}
+
+impl Server {
+    pub fn new(concurrency: usize, runner: JobRunner) -> Rc<Self> {
+        Rc::new(Self {
+            jobs: Rc::new(RefCell::new(Vec::new())),
+            permits: Rc::new(Semaphore::new(concurrency.max(1))),
+            runner,
+            next_id: Cell::new(1),
+        })
+    }
+
+    pub async fn run(self: Rc<Self>, listener: UnixListener) -> anyhow::Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = self.clone();
+
+            tokio::task::spawn_local(async move {
+                if let Err(e) = server.handle(stream).await {
+                    log::debug!("Daemon connection failed: {:#}", e);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, stream: UnixStream) -> anyhow::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let request = protocol::receive(&mut BufReader::new(reader)).await;
+
+        let response = match request {
+            Ok(Request::Enqueue { url }) => Response::Queued {
+                id: self.enqueue(url),
+            },
+            Ok(Request::Status) => Response::Status {
+                jobs: self.report(),
+            },
+            Err(e) => Response::Error {
+                message: format!("{:#}", e),
+            },
+        };
+
+        protocol::send(&mut writer, &response).await
+    }
+
+    pub fn enqueue(&self, url: String) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        self.jobs.borrow_mut().push(JobEntry {
+            job: Job {
+                id,
+                url: url.clone(),
+                file_name: None,
+                state: JobState::Queued,
+                progress: None,
+            },
+            tracking_file: None,
+        });
+
+        let jobs = self.jobs.clone();
+        let permits = self.permits.clone();
+        let runner = self.runner.clone();
+        let reporter = JobReporter {
+            id,
+            jobs: jobs.clone(),
+        };
+
+        tokio::task::spawn_local(async move {
+            let _permit = permits.acquire().await;
+            set_state(&jobs, id, JobState::Downloading);
+
+            let state = match runner(url, reporter).await {
+                Ok(()) => JobState::Done,
+                Err(e) => JobState::Failed(format!("{:#}", e)),
+            };
+            set_state(&jobs, id, state);
+        });
+
+        id
+    }
+
+    /// All jobs in the order they were queued, with current progress for running downloads.
+    pub fn jobs(&self) -> Vec<Job> {
+        self.jobs
+            .borrow()
+            .iter()
+            .map(|entry| {
+                let mut job = entry.job.clone();
+                if job.state == JobState::Downloading {
+                    job.progress = entry.tracking_file.as_ref().and_then(read_progress);
+                }
+                job
+            })
+            .collect()
+    }
+
+    /// Like [`Server::jobs`], but drops the finished and failed jobs once they're returned, so
+    /// each of them is only reported once.
+    pub fn report(&self) -> Vec<Job> {
+        let jobs = self.jobs();
+        self.jobs.borrow_mut().retain(|entry| entry.job.is_active());
+        jobs
+    }
+}
+
+fn set_state(jobs: &RefCell<Vec<JobEntry>>, id: u64, state: JobState) {
+    if let Some(entry) = jobs.borrow_mut().iter_mut().find(|e| e.job.id == id) {
+        entry.job.state = state;
+    }
+}
+
+fn read_progress(tracking_file: &PathBuf) -> Option<DownloadProgress> {
+    let content = fs::read_to_string(tracking_file).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::client;
+    use tempfile::TempDir;
+    use tokio::{sync::Notify, task::LocalSet};
+
+    fn blocking_runner(release: Rc<Notify>) -> JobRunner {
+        Rc::new(move |_url, _reporter| {
+            let release = release.clone();
+            Box::pin(async move {
+                release.notified().await;
+                Ok(())
+            })
+        })
+    }
+
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn enqueue_should_queue_jobs_beyond_concurrency_limit() -> anyhow::Result<()> {
+        LocalSet::new()
+            .run_until(async {
+                // Arrange
+                let release = Rc::new(Notify::new());
+                let sut = Server::new(1, blocking_runner(release.clone()));
+
+                // Act
+                sut.enqueue("first".to_string());
+                sut.enqueue("second".to_string());
+                settle().await;
+
+                // Assert
+                let states: Vec<_> = sut.jobs().into_iter().map(|j| j.state).collect();
+                assert_eq!(states, vec![JobState::Downloading, JobState::Queued]);
+
+                release.notify_one();
+                settle().await;
+                let states: Vec<_> = sut.jobs().into_iter().map(|j| j.state).collect();
+                assert_eq!(states, vec![JobState::Done, JobState::Downloading]);
+
+                Ok(())
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn enqueue_should_mark_job_failed_when_runner_fails() -> anyhow::Result<()> {
+        LocalSet::new()
+            .run_until(async {
+                // Arrange
+                let runner: JobRunner =
+                    Rc::new(|_url, _reporter| Box::pin(async { anyhow::bail!("link expired") }));
+                let sut = Server::new(2, runner);
+
+                // Act
+                sut.enqueue("nxm://expired".to_string());
+                settle().await;
+
+                // Assert
+                assert_eq!(
+                    sut.jobs()[0].state,
+                    JobState::Failed("link expired".to_string())
+                );
+
+                Ok(())
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn report_should_drop_finished_jobs_once_reported() -> anyhow::Result<()> {
+        LocalSet::new()
+            .run_until(async {
+                // Arrange
+                let release = Rc::new(Notify::new());
+                let sut = Server::new(1, blocking_runner(release.clone()));
+                sut.enqueue("first".to_string());
+                sut.enqueue("second".to_string());
+                settle().await;
+                release.notify_one();
+                settle().await;
+
+                // Act
+                let first = sut.report();
+                let second = sut.report();
+
+                // Assert
+                let states: Vec<_> = first.into_iter().map(|j| j.state).collect();
+                assert_eq!(states, vec![JobState::Done, JobState::Downloading]);
+                let states: Vec<_> = second.into_iter().map(|j| j.state).collect();
+                assert_eq!(states, vec![JobState::Downloading]);
+
+                Ok(())
+            })
+            .await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_should_answer_client_requests_over_socket() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let socket_path = tmp_dir.path().join("moma.sock");
+
+        LocalSet::new()
+            .run_until(async {
+                // Arrange
+                let listener = UnixListener::bind(&socket_path)?;
+                let sut = Server::new(1, blocking_runner(Rc::new(Notify::new())));
+                tokio::task::spawn_local(sut.run(listener));
+
+                // Act
+                let path = socket_path.clone();
+                let (id, jobs) = tokio::task::spawn_blocking(move || {
+                    let id = client::enqueue(&path, "nxm://queued")?;
+                    let jobs = client::status(&path)?;
+                    anyhow::Ok((id, jobs))
+                })
+                .await??;
+
+                // Assert
+                assert_eq!(id, 1);
+                assert_eq!(jobs.len(), 1);
+                assert_eq!(jobs[0].url, "nxm://queued");
+
+                Ok(())
+            })
+            .await
+    }
+}
@@ -0,0 +1,159 @@
+use std::fs;
+
+use owo_colors::OwoColorize;
+use reqwest::Url;
+
+use crate::{
+    config::Config,
+    games::{Game, workspace::Workspace},
+    mods::{
+        download_tracker::DownloadTracker, fomod::DefaultChooser, mod_list_store::ModListStore,
+    },
+    sources::{
+        github::{self, GitHub},
+        url::UrlDownloader,
+    },
+    types::{FileStatus, Mod, ModArchive},
+    ui::{print, prompt},
+};
+
+/// Where a script extender is published.
+pub enum ExtenderSource {
+    /// A direct link to a release archive.
+    Url(&'static str),
+
+    /// The latest release of a GitHub repository, picking the asset matching the glob.
+    GitHub {
+        repository: &'static str,
+        asset_pattern: &'static str,
+    },
+}
+
+pub struct ScriptExtender {
+    pub name: &'static str,
+    pub source: ExtenderSource,
+}
+
+impl ScriptExtender {
+    /// The staged archive of the extender if it was downloaded before.
+    pub fn find_staged(&self, mods: Vec<Mod>) -> Option<(Mod, ModArchive)> {
+        mods.into_iter().find_map(|m| {
+            let archive = m
+                .archives
+                .iter()
+                .filter(|a| matches!(a.status, FileStatus::Downloaded | FileStatus::Installed))
+                .find(|a| match &self.source {
+                    ExtenderSource::Url(url) => a.url.as_deref() == Some(url),
+                    ExtenderSource::GitHub { repository, .. } => {
+                        m.repository
+                            .as_deref()
+                            .is_some_and(|r| r.eq_ignore_ascii_case(repository))
+                            && a.version == m.version
+                    }
+                })
+                .cloned();
+            archive.map(|a| (m, a))
+        })
+    }
+
+    async fn download(
+        &self,
+        workspace: Workspace,
+        mod_list_store: &ModListStore,
+    ) -> anyhow::Result<(Mod, ModArchive)> {
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+
+        match &self.source {
+            ExtenderSource::Url(url) => {
+                println!("Downloading {} from {}", self.name, url.dimmed());
+
+                UrlDownloader::new(download_tracker)?
+                    .fetch(mod_list_store, self.name, &Url::parse(url)?, None)
+                    .await
+            }
+            ExtenderSource::GitHub {
+                repository,
+                asset_pattern,
+            } => {
+                println!(
+                    "Downloading the latest {} release from GitHub ({})",
+                    self.name,
+                    repository.dimmed()
+                );
+
+                GitHub::new(download_tracker)?
+                    .add_latest_release(
+                        mod_list_store,
+                        &github::parse_repository(repository)?,
+                        Some(self.name),
+                        Some(asset_pattern),
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+/// Downloads the script extender into the mod list and installs it like any other mod.
+pub async fn setup(config: &Config, game: &Game, extender: &ScriptExtender) -> anyhow::Result<()> {
+    if !prompt::confirm(&format!("Do you want to setup {}?", extender.name))? {
+        println!(
+            "{}",
+            format!("\nSkipping {} setup.", extender.name).yellow()
+        );
+        return Ok(());
+    }
+
+    let workspace = Workspace::new(game, config)?;
+    let mod_list_store = ModListStore::new(workspace.clone());
+
+    // Earlier versions extracted SKSE straight into the mods directory, outside the mod list.
+    // Other games never had that, a mod folder with the extender's name isn't ours to remove.
+    let legacy_dir = workspace.mods_dir().join(extender.name.to_lowercase());
+    if matches!(game, Game::SkyrimSE) && legacy_dir.exists() {
+        if prompt::confirm(&format!(
+            "{} already downloaded, do you want to overwrite?",
+            extender.name
+        ))? {
+            fs::remove_dir_all(&legacy_dir)?;
+        } else {
+            return Ok(());
+        }
+    }
+
+    println!(
+        "{}",
+        format!("\nSetting up {}\n", extender.name).bold().cyan()
+    );
+
+    let (m, archive) = match extender.find_staged(mod_list_store.read()?.mods) {
+        Some((m, archive)) => {
+            if matches!(archive.status, FileStatus::Installed)
+                && !prompt::confirm(&format!(
+                    "{} already installed, do you want to reinstall?",
+                    extender.name
+                ))?
+            {
+                return Ok(());
+            }
+            (m, archive)
+        }
+        None => extender.download(workspace, &mod_list_store).await?,
+    };
+
+    print::print_inline_status(&format!("Installing {}...", extender.name))?;
+
+    mod_list_store.install_archive(&m, &archive, &mut DefaultChooser)?;
+
+    print::print_inline_status("Done!")?;
+
+    println!(
+        "{}",
+        format!("\n\n{} installed successfully\n", extender.name)
+            .bold()
+            .underline()
+            .cyan()
+    );
+
+    Ok(())
+}
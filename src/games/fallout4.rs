@@ -0,0 +1,70 @@
+use crate::{
+    config::Config,
+    games::{
+        Game,
+        extender::{self, ExtenderSource, ScriptExtender},
+    },
+    sources::Source,
+};
+
+const F4SE: ScriptExtender = ScriptExtender {
+    name: "F4SE",
+    source: ExtenderSource::Url("https://f4se.silverlock.org/beta/f4se_0_06_23.7z"),
+};
+
+pub fn supported_sources() -> Vec<Source> {
+    vec![Source::Nexus]
+}
+
+pub fn game_mod_executable() -> &'static str {
+    "f4se_loader.exe"
+}
+
+pub fn data_dir() -> &'static str {
+    "Data"
+}
+
+pub fn data_folders() -> &'static [&'static str] {
+    &[
+        "meshes",
+        "textures",
+        "materials",
+        "scripts",
+        "f4se",
+        "mcm",
+        "interface",
+        "sound",
+        "music",
+        "seq",
+        "strings",
+        "shadersfx",
+        "lodsettings",
+        "vis",
+        "video",
+        "programs",
+    ]
+}
+
+pub fn data_extensions() -> &'static [&'static str] {
+    &["esp", "esm", "esl", "ba2"]
+}
+
+pub fn plugin_extensions() -> &'static [&'static str] {
+    &["esp", "esm", "esl"]
+}
+
+pub fn app_data_dir() -> &'static str {
+    "Fallout4"
+}
+
+pub fn root_extensions() -> &'static [&'static str] {
+    &["exe", "dll"]
+}
+
+pub fn lists_disabled_plugins() -> bool {
+    true
+}
+
+pub async fn setup(config: &Config) -> anyhow::Result<()> {
+    extender::setup(config, &Game::Fallout4, &F4SE).await
+}
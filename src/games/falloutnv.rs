@@ -0,0 +1,69 @@
+use crate::{
+    config::Config,
+    games::{
+        Game,
+        extender::{self, ExtenderSource, ScriptExtender},
+    },
+    sources::Source,
+};
+
+const NVSE: ScriptExtender = ScriptExtender {
+    name: "xNVSE",
+    source: ExtenderSource::GitHub {
+        repository: "xNVSE/NVSE",
+        asset_pattern: "nvse_*.7z",
+    },
+};
+
+pub fn supported_sources() -> Vec<Source> {
+    vec![Source::Nexus]
+}
+
+pub fn game_mod_executable() -> &'static str {
+    "nvse_loader.exe"
+}
+
+pub fn data_dir() -> &'static str {
+    "Data"
+}
+
+pub fn data_folders() -> &'static [&'static str] {
+    &[
+        "meshes",
+        "textures",
+        "nvse",
+        "menus",
+        "sound",
+        "music",
+        "shaders",
+        "fonts",
+        "lodsettings",
+        "lsdata",
+        "video",
+        "trees",
+    ]
+}
+
+pub fn data_extensions() -> &'static [&'static str] {
+    &["esp", "esm", "bsa"]
+}
+
+pub fn plugin_extensions() -> &'static [&'static str] {
+    &["esp", "esm"]
+}
+
+pub fn app_data_dir() -> &'static str {
+    "FalloutNV"
+}
+
+pub fn root_extensions() -> &'static [&'static str] {
+    &["exe", "dll"]
+}
+
+pub fn lists_disabled_plugins() -> bool {
+    false
+}
+
+pub async fn setup(config: &Config) -> anyhow::Result<()> {
+    extender::setup(config, &Game::FalloutNV, &NVSE).await
+}
@@ -1,22 +1,36 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 use crate::{config::Config, sources::Source, utils::fs::ExpandTilde};
 
+pub mod extender;
+pub mod fallout4;
+pub mod falloutnv;
+pub mod skyrimle;
 pub mod skyrimse;
 pub mod workspace;
 
 #[derive(clap::ValueEnum, EnumIter, Clone, Serialize, Deserialize)]
 pub enum Game {
     SkyrimSE,
+    SkyrimLE,
+    Fallout4,
+    FalloutNV,
 }
 
 impl Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Game::SkyrimSE => write!(f, "Skyrim Special Edition"),
+            Game::SkyrimLE => write!(f, "Skyrim Legendary Edition"),
+            Game::Fallout4 => write!(f, "Fallout 4"),
+            Game::FalloutNV => write!(f, "Fallout: New Vegas"),
         }
     }
 }
@@ -25,43 +39,277 @@ impl Game {
     pub fn id(&self) -> &'static str {
         match self {
             Game::SkyrimSE => "skyrimse",
+            Game::SkyrimLE => "skyrimle",
+            Game::Fallout4 => "fallout4",
+            Game::FalloutNV => "falloutnv",
         }
     }
 
     pub fn from_id(id: &str) -> Option<Self> {
         match id {
             "skyrimse" => Some(Game::SkyrimSE),
+            "skyrimle" => Some(Game::SkyrimLE),
+            "fallout4" => Some(Game::Fallout4),
+            "falloutnv" => Some(Game::FalloutNV),
             _ => None,
         }
     }
 
     pub fn default_game_path(&self, config: &Config) -> anyhow::Result<PathBuf> {
-        let path = match self {
-            Game::SkyrimSE => config
-                .steam_dir()
-                .join("steamapps")
-                .join("common")
-                .join("Skyrim Special Edition"),
+        let steam_dir_name = match self {
+            Game::SkyrimSE => "Skyrim Special Edition",
+            Game::SkyrimLE => "Skyrim",
+            Game::Fallout4 => "Fallout 4",
+            Game::FalloutNV => "Fallout New Vegas",
         };
 
+        let path = config
+            .steam_dir()
+            .join("steamapps")
+            .join("common")
+            .join(steam_dir_name);
+
         Ok(path.expand())
     }
 
     pub fn default_mod_sources(&self) -> Vec<Source> {
         match self {
             Game::SkyrimSE => skyrimse::supported_sources(),
+            Game::SkyrimLE => skyrimle::supported_sources(),
+            Game::Fallout4 => fallout4::supported_sources(),
+            Game::FalloutNV => falloutnv::supported_sources(),
         }
     }
 
     pub fn game_mod_executable(&self) -> &'static str {
         match self {
             Game::SkyrimSE => skyrimse::game_mod_executable(),
+            Game::SkyrimLE => skyrimle::game_mod_executable(),
+            Game::Fallout4 => fallout4::game_mod_executable(),
+            Game::FalloutNV => falloutnv::game_mod_executable(),
+        }
+    }
+
+    /// Directory inside the game's installation folder that holds plugins and assets.
+    pub fn data_dir(&self) -> &'static str {
+        match self {
+            Game::SkyrimSE => skyrimse::data_dir(),
+            Game::SkyrimLE => skyrimle::data_dir(),
+            Game::Fallout4 => fallout4::data_dir(),
+            Game::FalloutNV => falloutnv::data_dir(),
         }
     }
 
+    /// The name a directory at `relative` (relative to the game's root) should have. The data
+    /// directory keeps the game's own casing, everything inside it takes the casing of the same
+    /// directory in `game_dir` and is lowercased when the game has none, so folders from
+    /// different mods and the game end up in the same directory.
+    pub fn canonical_dir_name(&self, game_dir: &Path, relative: &Path) -> String {
+        let name = relative
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut components = relative.components();
+        let in_data_dir = components
+            .next()
+            .is_some_and(|c| c.as_os_str().eq_ignore_ascii_case(self.data_dir()));
+
+        match (in_data_dir, components.next()) {
+            (true, None) => self.data_dir().to_string(),
+            (true, Some(_)) => {
+                let parent = game_dir.join(relative.parent().unwrap_or(Path::new("")));
+                existing_dir_name(&parent, &name).unwrap_or_else(|| name.to_lowercase())
+            }
+            (false, _) => name,
+        }
+    }
+
+    /// Lower cased folder names that only appear directly inside the data directory.
+    pub fn data_folders(&self) -> &'static [&'static str] {
+        match self {
+            Game::SkyrimSE => skyrimse::data_folders(),
+            Game::SkyrimLE => skyrimle::data_folders(),
+            Game::Fallout4 => fallout4::data_folders(),
+            Game::FalloutNV => falloutnv::data_folders(),
+        }
+    }
+
+    /// Lower cased extensions of files that only appear directly inside the data directory.
+    pub fn data_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Game::SkyrimSE => skyrimse::data_extensions(),
+            Game::SkyrimLE => skyrimle::data_extensions(),
+            Game::Fallout4 => fallout4::data_extensions(),
+            Game::FalloutNV => falloutnv::data_extensions(),
+        }
+    }
+
+    /// Lower cased extensions of the plugin files the game loads.
+    pub fn plugin_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Game::SkyrimSE => skyrimse::plugin_extensions(),
+            Game::SkyrimLE => skyrimle::plugin_extensions(),
+            Game::Fallout4 => fallout4::plugin_extensions(),
+            Game::FalloutNV => falloutnv::plugin_extensions(),
+        }
+    }
+
+    /// Folder under `AppData/Local` in the Proton prefix where the game reads `plugins.txt` from.
+    pub fn app_data_dir(&self) -> &'static str {
+        match self {
+            Game::SkyrimSE => skyrimse::app_data_dir(),
+            Game::SkyrimLE => skyrimle::app_data_dir(),
+            Game::Fallout4 => fallout4::app_data_dir(),
+            Game::FalloutNV => falloutnv::app_data_dir(),
+        }
+    }
+
+    /// Whether `plugins.txt` lists disabled plugins too, marking the enabled ones with `*`. The
+    /// original Skyrim and New Vegas only list the enabled ones and load them by file time.
+    pub fn lists_disabled_plugins(&self) -> bool {
+        match self {
+            Game::SkyrimSE => skyrimse::lists_disabled_plugins(),
+            Game::SkyrimLE => skyrimle::lists_disabled_plugins(),
+            Game::Fallout4 => fallout4::lists_disabled_plugins(),
+            Game::FalloutNV => falloutnv::lists_disabled_plugins(),
+        }
+    }
+
+    /// Lower cased extensions of files that only appear in the game's installation directory.
+    pub fn root_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Game::SkyrimSE => skyrimse::root_extensions(),
+            Game::SkyrimLE => skyrimle::root_extensions(),
+            Game::Fallout4 => fallout4::root_extensions(),
+            Game::FalloutNV => falloutnv::root_extensions(),
+        }
+    }
+
+    /// Offers to install the game's script extender as a mod. New Vegas gets xNVSE, the community
+    /// continuation of the no longer maintained NVSE.
     pub async fn setup(&self, config: &Config) -> anyhow::Result<()> {
         match self {
             Game::SkyrimSE => skyrimse::setup(config).await,
+            Game::SkyrimLE => skyrimle::setup(config).await,
+            Game::Fallout4 => fallout4::setup(config).await,
+            Game::FalloutNV => falloutnv::setup(config).await,
+        }
+    }
+}
+
+fn existing_dir_name(parent: &Path, name: &str) -> Option<String> {
+    fs::read_dir(parent)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .find(|n| n.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+    use tempfile::TempDir;
+
+    /// Lays out a Steam library with each game's folder, script extender loader and data dir.
+    fn setup_steam_library() -> anyhow::Result<(TempDir, Config)> {
+        let tmp_dir = TempDir::new()?;
+        let config = Config::test(tmp_dir.path().to_owned());
+
+        for (folder, loader) in [
+            ("Skyrim Special Edition", "skse64_loader.exe"),
+            ("Skyrim", "skse_loader.exe"),
+            ("Fallout 4", "f4se_loader.exe"),
+            ("Fallout New Vegas", "nvse_loader.exe"),
+        ] {
+            let game_dir = config.steam_dir().join("steamapps/common").join(folder);
+            fs::create_dir_all(game_dir.join("Data"))?;
+            fs::write(game_dir.join(loader), "")?;
+        }
+
+        Ok((tmp_dir, config))
+    }
+
+    #[test]
+    fn default_game_path_should_point_at_installed_game_with_its_loader() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, config) = setup_steam_library()?;
+
+        for game in Game::iter() {
+            // Act
+            let actual = game.default_game_path(&config)?;
+
+            // Assert
+            assert!(
+                actual.join(game.game_mod_executable()).is_file(),
+                "{} has no loader at '{}'",
+                game,
+                actual.display()
+            );
+            assert!(actual.join(game.data_dir()).is_dir(), "{}", game);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_id_should_return_game_for_each_id() -> anyhow::Result<()> {
+        for game in Game::iter() {
+            // Act
+            let actual = Game::from_id(game.id());
+
+            // Assert
+            assert_eq!(actual.map(|g| g.id()), Some(game.id()));
         }
+
+        assert!(Game::from_id("oblivion").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plugin_settings_should_match_each_game() -> anyhow::Result<()> {
+        // Act & Assert
+        assert_eq!(Game::SkyrimLE.plugin_extensions(), &["esp", "esm"]);
+        assert_eq!(Game::FalloutNV.plugin_extensions(), &["esp", "esm"]);
+        assert_eq!(Game::Fallout4.plugin_extensions(), &["esp", "esm", "esl"]);
+        assert!(Game::Fallout4.data_extensions().contains(&"ba2"));
+
+        assert_eq!(Game::SkyrimLE.app_data_dir(), "Skyrim");
+        assert_eq!(Game::Fallout4.app_data_dir(), "Fallout4");
+        assert_eq!(Game::FalloutNV.app_data_dir(), "FalloutNV");
+
+        assert!(Game::SkyrimSE.lists_disabled_plugins());
+        assert!(Game::Fallout4.lists_disabled_plugins());
+        assert!(!Game::SkyrimLE.lists_disabled_plugins());
+        assert!(!Game::FalloutNV.lists_disabled_plugins());
+
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_dir_name_should_take_casing_of_game_dir() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        fs::create_dir_all(tmp_dir.path().join("Data/Scripts/Source"))?;
+        let sut = Game::SkyrimSE;
+
+        // Act
+        let data = sut.canonical_dir_name(tmp_dir.path(), Path::new("DATA"));
+        let scripts = sut.canonical_dir_name(tmp_dir.path(), Path::new("Data/scripts"));
+        let source = sut.canonical_dir_name(tmp_dir.path(), Path::new("Data/Scripts/SOURCE"));
+        let textures = sut.canonical_dir_name(tmp_dir.path(), Path::new("Data/Textures"));
+        let outside = sut.canonical_dir_name(tmp_dir.path(), Path::new("SKSE"));
+
+        // Assert
+        assert_eq!(data, "Data");
+        assert_eq!(scripts, "Scripts");
+        assert_eq!(source, "Source");
+        assert_eq!(textures, "textures");
+        assert_eq!(outside, "SKSE");
+
+        Ok(())
     }
 }
@@ -0,0 +1,72 @@
+use crate::{
+    config::Config,
+    games::{
+        Game,
+        extender::{self, ExtenderSource, ScriptExtender},
+    },
+    sources::Source,
+};
+
+const SKSE: ScriptExtender = ScriptExtender {
+    name: "SKSE",
+    source: ExtenderSource::Url("https://skse.silverlock.org/beta/skse_1_07_03.7z"),
+};
+
+pub fn supported_sources() -> Vec<Source> {
+    vec![Source::Nexus]
+}
+
+pub fn game_mod_executable() -> &'static str {
+    "skse_loader.exe"
+}
+
+pub fn data_dir() -> &'static str {
+    "Data"
+}
+
+pub fn data_folders() -> &'static [&'static str] {
+    &[
+        "meshes",
+        "textures",
+        "scripts",
+        "skse",
+        "interface",
+        "sound",
+        "music",
+        "seq",
+        "strings",
+        "shaders",
+        "lodsettings",
+        "grass",
+        "video",
+        "facegen",
+        "dialogueviews",
+        "calientetools",
+        "tools",
+        "source",
+    ]
+}
+
+pub fn data_extensions() -> &'static [&'static str] {
+    &["esp", "esm", "bsa"]
+}
+
+pub fn plugin_extensions() -> &'static [&'static str] {
+    &["esp", "esm"]
+}
+
+pub fn app_data_dir() -> &'static str {
+    "Skyrim"
+}
+
+pub fn root_extensions() -> &'static [&'static str] {
+    &["exe", "dll"]
+}
+
+pub fn lists_disabled_plugins() -> bool {
+    false
+}
+
+pub async fn setup(config: &Config) -> anyhow::Result<()> {
+    extender::setup(config, &Game::SkyrimLE, &SKSE).await
+}
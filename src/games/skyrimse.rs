@@ -1,18 +1,16 @@
-use std::{
-    fs::{self, File},
-    io::Write,
-};
-
-use owo_colors::OwoColorize;
-
 use crate::{
     config::Config,
-    games::{Game, workspace::Workspace},
+    games::{
+        Game,
+        extender::{self, ExtenderSource, ScriptExtender},
+    },
     sources::Source,
-    ui::{print, prompt},
-    utils::{self},
 };
-use reqwest::get;
+
+const SKSE: ScriptExtender = ScriptExtender {
+    name: "SKSE",
+    source: ExtenderSource::Url("https://skse.silverlock.org/beta/skse64_2_02_06.7z"),
+};
 
 pub fn supported_sources() -> Vec<Source> {
     vec![Source::Nexus]
@@ -22,55 +20,54 @@ pub fn game_mod_executable() -> &'static str {
     "skse64_loader.exe"
 }
 
-pub async fn setup(config: &Config) -> anyhow::Result<()> {
-    if !prompt::confirm("Do you want to setup SKSE?")? {
-        println!("{}", "\nSkipping SKSE setup.".yellow());
-        return Ok(());
-    }
-
-    let workspace = Workspace::new(&Game::SkyrimSE, config)?;
-
-    let skse_output_dir = workspace.mods_dir().join("skse");
-    let skse_archive_path = workspace.cache_dir().join("skse.7z");
-
-    if skse_output_dir.exists() {
-        if prompt::confirm("SKSE already downloaded, do you want to overwrite?")? {
-            fs::remove_dir_all(&skse_output_dir)?;
-        } else {
-            return Ok(());
-        }
-    }
-
-    println!("{}", "\nSetting up SKSE\n".bold().cyan());
+pub fn data_dir() -> &'static str {
+    "Data"
+}
 
-    print::print_inline_status(
-        format!(
-            "Downloading SKSE to \"{}\"",
-            workspace.cache_dir().display()
-        )
-        .as_ref(),
-    )?;
+pub fn data_folders() -> &'static [&'static str] {
+    &[
+        "meshes",
+        "textures",
+        "scripts",
+        "skse",
+        "interface",
+        "sound",
+        "music",
+        "seq",
+        "strings",
+        "shaders",
+        "lodsettings",
+        "grass",
+        "video",
+        "facegen",
+        "dialogueviews",
+        "calientetools",
+        "nemesis_engine",
+        "netscriptframework",
+        "source",
+    ]
+}
 
-    let bytes = get("https://skse.silverlock.org/beta/skse64_2_02_06.7z")
-        .await?
-        .bytes()
-        .await?;
-    let mut out = File::create(&skse_archive_path)?;
-    out.write_all(&bytes)?;
+pub fn data_extensions() -> &'static [&'static str] {
+    &["esp", "esm", "esl", "bsa"]
+}
 
-    print::print_inline_status("Extracting archive...")?;
+pub fn plugin_extensions() -> &'static [&'static str] {
+    &["esp", "esm", "esl"]
+}
 
-    utils::fs::extract_archive(&skse_archive_path, &skse_output_dir, true)?;
+pub fn app_data_dir() -> &'static str {
+    "Skyrim Special Edition"
+}
 
-    print::print_inline_status("Done!")?;
+pub fn root_extensions() -> &'static [&'static str] {
+    &["exe", "dll"]
+}
 
-    println!(
-        "{}",
-        "\n\nSKSE installed successfully\n"
-            .bold()
-            .underline()
-            .cyan()
-    );
+pub fn lists_disabled_plugins() -> bool {
+    true
+}
 
-    Ok(())
+pub async fn setup(config: &Config) -> anyhow::Result<()> {
+    extender::setup(config, &Game::SkyrimSE, &SKSE).await
 }
@@ -13,7 +13,7 @@ use crate::{
 
 const OVERLAY: &str = ".overlay";
 const CACHE: &str = ".cache";
-const MERGED: &str = "merged";
+const LAYERS: &str = "layers";
 const WORK: &str = "work";
 const ACTIVE: &str = "active";
 const MODS: &str = "mods";
@@ -21,10 +21,15 @@ const STAGING: &str = "staging";
 const SINK: &str = "sink";
 const PROTON: &str = "proton";
 const TRACKING: &str = "tracking";
+const EXTRACT: &str = "extract";
 
 pub const ARCHIVES: &str = "archives";
 
 pub const MOD_LIST_FILE: &str = "mod-list.json";
+pub const PLUGIN_LIST_FILE: &str = "plugin-list.json";
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+
+const PROTON_APP_DATA: &str = "pfx/drive_c/users/steamuser/AppData/Local";
 
 /// Represents the game-specific working directory structure
 #[derive(Clone)]
@@ -45,12 +50,16 @@ impl Workspace {
         self.cache_dir().join(TRACKING)
     }
 
+    pub fn extract_dir(&self) -> PathBuf {
+        self.cache_dir().join(EXTRACT)
+    }
+
     pub fn overlay_dir(&self) -> PathBuf {
         self.root.join(OVERLAY)
     }
 
-    pub fn overlay_merged_dir(&self) -> PathBuf {
-        self.overlay_dir().join(MERGED)
+    pub fn overlay_layers_dir(&self) -> PathBuf {
+        self.overlay_dir().join(LAYERS)
     }
 
     pub fn overlay_work_dir(&self) -> PathBuf {
@@ -69,6 +78,10 @@ impl Workspace {
         self.game.path.clone()
     }
 
+    pub fn game(&self) -> &Game {
+        &self.game.game
+    }
+
     pub fn staging_dir(&self) -> PathBuf {
         self.root.join(STAGING)
     }
@@ -81,6 +94,13 @@ impl Workspace {
         self.root.join(PROTON)
     }
 
+    /// The game's `AppData/Local` folder inside the Proton prefix.
+    pub fn app_data_dir(&self) -> PathBuf {
+        self.proton_work_dir()
+            .join(PROTON_APP_DATA)
+            .join(self.game.game.app_data_dir())
+    }
+
     pub fn proton_binary(&self) -> PathBuf {
         self.game.proton_dir.join("proton")
     }
@@ -106,7 +126,6 @@ impl Workspace {
 
         let mut paths = vec![
             self.proton_work_dir(),
-            self.overlay_merged_dir(),
             self.overlay_work_dir(),
             self.active_dir(),
             self.tracking_dir(),
@@ -227,6 +246,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn extract_dir_should_return_correct_path() -> anyhow::Result<()> {
+        // Arrange
+        let game = Game::SkyrimSE;
+        let config = setup(&game)?;
+
+        let sut = Workspace::new(&game, &config)?;
+
+        // Act
+        let actual = sut.extract_dir();
+
+        // Assert
+        assert_eq!(
+            actual,
+            config
+                .base_working_dir()
+                .join(game.id())
+                .join(CACHE)
+                .join(EXTRACT)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn overlay_dir_should_return_correct_path() -> anyhow::Result<()> {
         // Arrange
@@ -248,7 +291,7 @@ mod tests {
     }
 
     #[test]
-    fn overlay_merged_dir_should_return_correct_path() -> anyhow::Result<()> {
+    fn overlay_layers_dir_should_return_correct_path() -> anyhow::Result<()> {
         // Arrange
         let game = Game::SkyrimSE;
         let config = setup(&game)?;
@@ -256,7 +299,7 @@ mod tests {
         let sut = Workspace::new(&game, &config)?;
 
         // Act
-        let actual = sut.overlay_merged_dir();
+        let actual = sut.overlay_layers_dir();
 
         // Assert
         assert_eq!(
@@ -265,7 +308,7 @@ mod tests {
                 .base_working_dir()
                 .join(game.id())
                 .join(OVERLAY)
-                .join(MERGED)
+                .join(LAYERS)
         );
 
         Ok(())
@@ -408,6 +451,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn app_data_dir_should_return_correct_path() -> anyhow::Result<()> {
+        // Arrange
+        let game = Game::SkyrimSE;
+        let config = setup(&game)?;
+
+        let sut = Workspace::new(&game, &config)?;
+
+        // Act
+        let actual = sut.app_data_dir();
+
+        // Assert
+        assert_eq!(
+            actual,
+            config
+                .base_working_dir()
+                .join(game.id())
+                .join(PROTON)
+                .join(PROTON_APP_DATA)
+                .join(game.app_data_dir())
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn proton_binary_should_return_correct_path() -> anyhow::Result<()> {
         // Arrange
@@ -469,7 +537,7 @@ mod tests {
             .filter(|d| is_leaf(d).is_ok())
             .collect();
 
-        assert_eq!(actual.len(), 7);
+        assert_eq!(actual.len(), 6);
 
         let folder_names: Vec<String> = actual
             .iter()
@@ -477,7 +545,6 @@ mod tests {
             .collect();
 
         assert!(folder_names.contains(&PROTON.to_string()));
-        assert!(folder_names.contains(&MERGED.to_string()));
         assert!(folder_names.contains(&WORK.to_string()));
         assert!(folder_names.contains(&ACTIVE.to_string()));
         assert!(folder_names.contains(&TRACKING.to_string()));
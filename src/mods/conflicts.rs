@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, HashMap};
+
+use walkdir::WalkDir;
+
+use crate::types::ModLayer;
+
+/// A file provided by more than one mod.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileConflict {
+    /// Path relative to the mod folder, as it's named in the winning mod.
+    pub path: String,
+    pub winner: String,
+    /// Mods whose version of the file is hidden, from highest to lowest priority.
+    pub losers: Vec<String>,
+}
+
+/// How many files a single mod overwrites in, or loses to, other mods.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConflictSummary {
+    pub label: String,
+    pub overwrites: Vec<(String, usize)>,
+    pub overwritten_by: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Default)]
+pub struct ConflictReport {
+    pub conflicts: Vec<FileConflict>,
+    pub summaries: Vec<ConflictSummary>,
+}
+
+/// Finds all files that are provided by more than one layer. Layers are expected from lowest to
+/// highest priority, same as `ModListStore::layers`. Paths are compared case-insensitively since
+/// the game runs on a case-insensitive file system.
+pub fn find_conflicts(layers: &[ModLayer]) -> anyhow::Result<ConflictReport> {
+    let mut providers: BTreeMap<String, Vec<(usize, String)>> = BTreeMap::new();
+
+    for (index, layer) in layers.iter().enumerate() {
+        for entry in WalkDir::new(&layer.path).min_depth(1) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative = entry
+                .path()
+                .strip_prefix(&layer.path)?
+                .to_string_lossy()
+                .to_string();
+
+            providers
+                .entry(relative.to_lowercase())
+                .or_default()
+                .push((index, relative));
+        }
+    }
+
+    let mut overwrites: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut conflicts = Vec::new();
+
+    for mut files in providers.into_values().filter(|f| f.len() > 1) {
+        files.sort_by_key(|(index, _)| *index);
+
+        for (i, (lower, _)) in files.iter().enumerate() {
+            for (higher, _) in &files[i + 1..] {
+                *overwrites.entry((*higher, *lower)).or_default() += 1;
+            }
+        }
+
+        let (winner, path) = files.pop().unwrap();
+        conflicts.push(FileConflict {
+            path,
+            winner: layers[winner].label.clone(),
+            losers: files
+                .iter()
+                .rev()
+                .map(|(index, _)| layers[*index].label.clone())
+                .collect(),
+        });
+    }
+
+    let summaries = layers
+        .iter()
+        .enumerate()
+        .map(|(index, layer)| {
+            let count_against = |other: usize, key: (usize, usize)| {
+                overwrites
+                    .get(&key)
+                    .map(|count| (layers[other].label.clone(), *count))
+            };
+
+            ConflictSummary {
+                label: layer.label.clone(),
+                overwrites: (0..layers.len())
+                    .filter_map(|other| count_against(other, (index, other)))
+                    .collect(),
+                overwritten_by: (0..layers.len())
+                    .filter_map(|other| count_against(other, (other, index)))
+                    .collect(),
+            }
+        })
+        .filter(|s| !s.overwrites.is_empty() || !s.overwritten_by.is_empty())
+        .collect();
+
+    Ok(ConflictReport {
+        conflicts,
+        summaries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::Path};
+    use tempfile::TempDir;
+
+    fn layer(root: &Path, dir_name: &str, files: &[&str]) -> anyhow::Result<ModLayer> {
+        let path = root.join(dir_name);
+
+        for file in files {
+            let file_path = path.join(file);
+            fs::create_dir_all(file_path.parent().unwrap())?;
+            fs::write(file_path, "")?;
+        }
+
+        Ok(ModLayer {
+            dir_name: dir_name.to_string(),
+            label: format!("Mod {}", dir_name),
+            path,
+        })
+    }
+
+    #[test]
+    fn find_conflicts_should_return_empty_report_when_no_files_overlap() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let layers = vec![
+            layer(tmp_dir.path(), "1", &["Data/a.esp"])?,
+            layer(tmp_dir.path(), "2", &["Data/b.esp"])?,
+        ];
+
+        // Act
+        let actual = find_conflicts(&layers)?;
+
+        // Assert
+        assert!(actual.conflicts.is_empty());
+        assert!(actual.summaries.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_conflicts_should_let_highest_layer_win_ignoring_case() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let layers = vec![
+            layer(tmp_dir.path(), "1", &["Data/Textures/a.dds"])?,
+            layer(tmp_dir.path(), "2", &["data/textures/A.dds"])?,
+            layer(tmp_dir.path(), "3", &["Data/TEXTURES/a.DDS", "Data/c.esp"])?,
+        ];
+
+        // Act
+        let actual = find_conflicts(&layers)?;
+
+        // Assert
+        assert_eq!(
+            actual.conflicts,
+            vec![FileConflict {
+                path: Path::new("Data/TEXTURES/a.DDS")
+                    .to_string_lossy()
+                    .to_string(),
+                winner: "Mod 3".to_string(),
+                losers: vec!["Mod 2".to_string(), "Mod 1".to_string()],
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_conflicts_should_count_overwritten_files_per_mod() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let layers = vec![
+            layer(
+                tmp_dir.path(),
+                "1",
+                &["Data/a.dds", "Data/b.dds", "Data/c.dds"],
+            )?,
+            layer(tmp_dir.path(), "2", &["Data/a.dds", "Data/b.dds"])?,
+            layer(tmp_dir.path(), "3", &["Data/c.dds"])?,
+        ];
+
+        // Act
+        let actual = find_conflicts(&layers)?;
+
+        // Assert
+        assert_eq!(
+            actual.summaries,
+            vec![
+                ConflictSummary {
+                    label: "Mod 1".to_string(),
+                    overwrites: vec![],
+                    overwritten_by: vec![("Mod 2".to_string(), 2), ("Mod 3".to_string(), 1)],
+                },
+                ConflictSummary {
+                    label: "Mod 2".to_string(),
+                    overwrites: vec![("Mod 1".to_string(), 2)],
+                    overwritten_by: vec![],
+                },
+                ConflictSummary {
+                    label: "Mod 3".to_string(),
+                    overwrites: vec![("Mod 1".to_string(), 1)],
+                    overwritten_by: vec![],
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
+use anyhow::bail;
+use md5::{Digest, Md5};
+use sha2::Sha256;
 use std::{
-    fs::{self, File},
-    io::{ErrorKind, Write},
+    fs::{self, File, OpenOptions},
+    future::Future,
+    io::{self, ErrorKind, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     time::{Instant, SystemTime, UNIX_EPOCH},
 };
@@ -9,9 +13,35 @@ use tokio::io::{AsyncRead, AsyncReadExt};
 use crate::{
     games::workspace::Workspace,
     mods::mod_list_store::ModListStore,
-    types::{DownloadProgress, FileStatus},
+    types::{DownloadProgress, FileStatus, ModArchive},
 };
 
+/// What was actually received for a download.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Checksum {
+    pub md5: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl Checksum {
+    /// Whether the download matches the hash and size the mod source declared for the archive.
+    /// Values the source didn't declare are not checked.
+    pub fn matches(&self, archive: &ModArchive) -> bool {
+        let md5_matches = archive
+            .md5
+            .as_ref()
+            .is_none_or(|md5| md5.eq_ignore_ascii_case(&self.md5));
+        let sha256_matches = archive
+            .sha256
+            .as_ref()
+            .is_none_or(|sha256| sha256.eq_ignore_ascii_case(&self.sha256));
+        let size_matches = archive.size.is_none_or(|size| size == self.size);
+
+        md5_matches && sha256_matches && size_matches
+    }
+}
+
 #[derive(Clone)]
 pub struct DownloadTracker {
     workspace: Workspace,
@@ -39,6 +69,7 @@ impl DownloadTracker {
 
                 let tracking_file = self.tracking_file(archive.file_uid);
                 let mut is_failed = false;
+                let mut is_resumable = false;
                 let mut reason = "";
 
                 let contents = match fs::read_to_string(&tracking_file) {
@@ -57,6 +88,7 @@ impl DownloadTracker {
                             // Age in seconds, allow idle for 1 min.
                             if age > 60 {
                                 is_failed = true;
+                                is_resumable = true;
                                 reason = "interrupted";
                             }
                         }
@@ -71,7 +103,14 @@ impl DownloadTracker {
                     archive.status = FileStatus::Failed(reason.into());
                     cancelled_files.push(archive.file_name.to_string());
 
-                    if let Err(e) = fs::remove_file(&tracking_file) {
+                    // An interrupted download keeps its progress so it can be resumed.
+                    let removed = if is_resumable {
+                        Ok(())
+                    } else {
+                        fs::remove_file(&tracking_file)
+                    };
+
+                    if let Err(e) = removed {
                         if e.kind() != ErrorKind::NotFound {
                             return Err(e.into());
                         }
@@ -89,19 +128,54 @@ impl DownloadTracker {
         Ok(cancelled_files.len())
     }
 
+    /// How many bytes of a previous download can be kept. This is the last progress that was
+    /// tracked, as long as the partial file on disk holds at least that many bytes.
+    pub fn resume_offset(&self, dest_path: &Path, progress_file: &Path) -> anyhow::Result<u64> {
+        let file_size = match fs::metadata(dest_path) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let progress_bytes = fs::read_to_string(progress_file)
+            .ok()
+            .and_then(|c| serde_json::from_str::<DownloadProgress>(&c).ok())
+            .map_or(0, |p| p.progress_bytes);
+
+        Ok(progress_bytes.min(file_size))
+    }
+
+    /// Writes the stream to `dest_path`. With a non-zero `offset` the file is truncated to that
+    /// length and the stream is appended, `total_size` is always the size of the complete file.
+    /// Returns the checksum of the complete file.
     pub async fn stream_to_file_with_tracking<R: AsyncRead + Unpin>(
         &self,
         mut stream: R,
         dest_path: &Path,
+        offset: u64,
         total_size: u64,
         progress_file: &Path,
         file_name: String,
-    ) -> anyhow::Result<()> {
-        let mut file = File::create(dest_path)?;
+    ) -> anyhow::Result<Checksum> {
+        let mut md5 = Md5::new();
+        let mut sha256 = Sha256::new();
+        let mut file = if offset > 0 {
+            let mut file = OpenOptions::new().read(true).write(true).open(dest_path)?;
+            file.set_len(offset)?;
+
+            // The part that's already on disk needs to be part of the hashes as well.
+            io::copy(&mut file, &mut md5)?;
+            file.seek(SeekFrom::Start(0))?;
+            io::copy(&mut file, &mut sha256)?;
+            file.seek(SeekFrom::End(0))?;
+            file
+        } else {
+            File::create(dest_path)?
+        };
         let mut buffer = [0u8; 8192];
 
         let start_unix = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        let mut downloaded: u64 = 0;
+        let mut downloaded: u64 = offset;
         let mut last_written = Instant::now();
 
         loop {
@@ -111,6 +185,8 @@ impl DownloadTracker {
             }
 
             file.write_all(&buffer[..read_bytes])?;
+            md5.update(&buffer[..read_bytes]);
+            sha256.update(&buffer[..read_bytes]);
             downloaded += read_bytes as u64;
 
             if last_written.elapsed().as_millis() > 500 {
@@ -135,7 +211,59 @@ impl DownloadTracker {
             }
         }
 
-        Ok(())
+        Ok(Checksum {
+            md5: to_hex(&md5.finalize()),
+            sha256: to_hex(&sha256.finalize()),
+            size: downloaded,
+        })
+    }
+
+    /// Awaits the download of `archive` into its archive path. A download that fails or doesn't
+    /// match the checksum marks the archive as failed, a corrupted file is removed as well since it
+    /// can't be resumed and the next attempt has to start over.
+    pub async fn checked_download(
+        &self,
+        mod_uid: u64,
+        archive: &ModArchive,
+        download: impl Future<Output = anyhow::Result<Checksum>>,
+    ) -> anyhow::Result<Checksum> {
+        let checksum = match download.await {
+            Ok(checksum) => checksum,
+            Err(err) => {
+                self.mod_list_store
+                    .update_archive(&mod_uid, &archive.file_uid, |a| {
+                        a.status = FileStatus::Failed(format!("{err:#}"))
+                    })?;
+                return Err(err);
+            }
+        };
+
+        if !checksum.matches(archive) {
+            if let Some(archive_path) = &archive.archive_path {
+                fs::remove_file(archive_path)?;
+            }
+
+            self.mod_list_store
+                .update_archive(&mod_uid, &archive.file_uid, |a| {
+                    a.status = FileStatus::Failed("checksum mismatch".to_string())
+                })?;
+
+            let (expected, actual) = match &archive.sha256 {
+                Some(sha256) => (format!("sha256 {}", sha256), checksum.sha256),
+                None => (
+                    format!("md5 {}", archive.md5.as_deref().unwrap_or("-")),
+                    checksum.md5,
+                ),
+            };
+            bail!(
+                "Checksum mismatch for '{}' (expected {}, got {})",
+                archive.file_name,
+                expected,
+                actual
+            );
+        }
+
+        Ok(checksum)
     }
 
     pub fn ensure_tracking_file(&self, file_uid: u64) -> anyhow::Result<PathBuf> {
@@ -155,15 +283,19 @@ impl DownloadTracker {
     }
 }
 
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         config::{Config, GameConfig},
         games::Game,
-        types::{Mod, ModArchive},
+        types::Mod,
     };
-    use std::{fs::OpenOptions, io::Cursor};
+    use std::io::Cursor;
     use tempfile::TempDir;
 
     fn setup_workspace() -> anyhow::Result<Workspace> {
@@ -217,6 +349,7 @@ mod tests {
             uid: 1,
             name: "Test mod".to_string(),
             archives: vec![],
+            ..Default::default()
         };
 
         let archive = ModArchive {
@@ -224,6 +357,7 @@ mod tests {
             file_name: "test.7z".to_string(),
             status: FileStatus::Downloaded,
             archive_path: None,
+            ..Default::default()
         };
 
         mod_list_store.add_archive(&m, archive)?;
@@ -255,6 +389,7 @@ mod tests {
             uid: 1,
             name: "Test mod".to_string(),
             archives: vec![],
+            ..Default::default()
         };
 
         let archive = ModArchive {
@@ -262,6 +397,7 @@ mod tests {
             file_name: "test.7z".to_string(),
             status: FileStatus::Downloading,
             archive_path: None,
+            ..Default::default()
         };
 
         mod_list_store.add_archive(&m, archive.clone())?;
@@ -309,6 +445,7 @@ mod tests {
             uid: 1,
             name: "Test mod".to_string(),
             archives: vec![],
+            ..Default::default()
         };
 
         let archive = ModArchive {
@@ -316,6 +453,7 @@ mod tests {
             file_name: "test.7z".to_string(),
             status: FileStatus::Downloading,
             archive_path: None,
+            ..Default::default()
         };
 
         mod_list_store.add_archive(&m, archive.clone())?;
@@ -365,6 +503,7 @@ mod tests {
             uid: 1,
             name: "Test mod".to_string(),
             archives: vec![],
+            ..Default::default()
         };
 
         let archive = ModArchive {
@@ -372,6 +511,7 @@ mod tests {
             file_name: "test.7z".to_string(),
             status: FileStatus::Downloading,
             archive_path: None,
+            ..Default::default()
         };
 
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
@@ -416,6 +556,7 @@ mod tests {
             actual_archive.status,
             FileStatus::Failed("interrupted".to_string())
         );
+        assert!(sut.tracking_file(archive.file_uid).exists());
 
         Ok(())
     }
@@ -434,6 +575,7 @@ mod tests {
             uid: 1,
             name: "Test mod".to_string(),
             archives: vec![],
+            ..Default::default()
         };
 
         let archive = ModArchive {
@@ -441,6 +583,7 @@ mod tests {
             file_name: "test.7z".to_string(),
             status: FileStatus::Downloading,
             archive_path: None,
+            ..Default::default()
         };
 
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
@@ -492,6 +635,7 @@ mod tests {
         sut.stream_to_file_with_tracking(
             Cursor::new(&data[..]),
             &dest_path,
+            0,
             total_size,
             &progress_file,
             "test.txt".to_string(),
@@ -529,6 +673,7 @@ mod tests {
         sut.stream_to_file_with_tracking(
             Cursor::new(data.clone()),
             &dest_path,
+            0,
             total_size,
             &progress_path,
             "file.bin".to_string(),
@@ -563,6 +708,7 @@ mod tests {
             Cursor::new(vec![]),
             &dest_path,
             0,
+            0,
             &progress_path,
             "empty.txt".to_string(),
         )
@@ -576,6 +722,170 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn stream_should_append_to_partial_file_when_offset_given() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup_workspace()?;
+        workspace.prepare_file_system()?;
+
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let sut = DownloadTracker::new(workspace.clone(), mod_list_store.clone());
+
+        let tmp = TempDir::new()?;
+        let dest_path = tmp.path().join("out.txt");
+        let progress_path = tmp.path().join("progress.json");
+        fs::write(&dest_path, "hello garbage")?;
+
+        // Act
+        sut.stream_to_file_with_tracking(
+            Cursor::new(&b" world"[..]),
+            &dest_path,
+            5,
+            11,
+            &progress_path,
+            "out.txt".to_string(),
+        )
+        .await?;
+
+        // Assert
+        assert_eq!(fs::read_to_string(&dest_path)?, "hello world");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_should_return_checksum_of_complete_file() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup_workspace()?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let sut = DownloadTracker::new(workspace.clone(), mod_list_store.clone());
+
+        let tmp = TempDir::new()?;
+        let dest_path = tmp.path().join("out.txt");
+        let progress_path = tmp.path().join("progress.json");
+        fs::write(&dest_path, "hello")?;
+
+        // Act
+        let actual = sut
+            .stream_to_file_with_tracking(
+                Cursor::new(&b" world"[..]),
+                &dest_path,
+                5,
+                11,
+                &progress_path,
+                "out.txt".to_string(),
+            )
+            .await?;
+
+        // Assert
+        assert_eq!(
+            actual,
+            Checksum {
+                md5: "5eb63bbbe01eeed093cb22bb8f5acdc3".to_string(),
+                sha256: "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+                    .to_string(),
+                size: 11,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn checksum_matches_should_compare_declared_values_only() {
+        // Arrange
+        let checksum = Checksum {
+            md5: "5eb63bbbe01eeed093cb22bb8f5acdc3".to_string(),
+            sha256: "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9".to_string(),
+            size: 11,
+        };
+        let archive = |md5: Option<&str>, size| ModArchive {
+            md5: md5.map(str::to_string),
+            size,
+            ..Default::default()
+        };
+
+        // Act & Assert
+        assert!(checksum.matches(&archive(None, None)));
+        assert!(checksum.matches(&archive(Some("5EB63BBBE01EEED093CB22BB8F5ACDC3"), Some(11))));
+        assert!(!checksum.matches(&archive(Some("d41d8cd98f00b204e9800998ecf8427e"), None)));
+        assert!(!checksum.matches(&archive(None, Some(10))));
+    }
+
+    #[test]
+    fn checksum_matches_should_compare_declared_sha256() {
+        // Arrange
+        let checksum = Checksum {
+            md5: "5eb63bbbe01eeed093cb22bb8f5acdc3".to_string(),
+            sha256: "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9".to_string(),
+            size: 11,
+        };
+        let archive = |sha256: &str| ModArchive {
+            sha256: Some(sha256.to_string()),
+            ..Default::default()
+        };
+
+        // Act & Assert
+        assert!(checksum.matches(&archive(
+            "B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9"
+        )));
+        assert!(!checksum.matches(&archive(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        )));
+    }
+
+    #[test]
+    fn resume_offset_should_return_zero_when_no_partial_file() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup_workspace()?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let sut = DownloadTracker::new(workspace.clone(), mod_list_store);
+
+        let tmp = TempDir::new()?;
+
+        // Act
+        let actual = sut.resume_offset(&tmp.path().join("out.7z"), &tmp.path().join("1.json"))?;
+
+        // Assert
+        assert_eq!(actual, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resume_offset_should_return_tracked_progress_capped_at_file_size() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup_workspace()?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let sut = DownloadTracker::new(workspace.clone(), mod_list_store);
+
+        let tmp = TempDir::new()?;
+        let dest_path = tmp.path().join("out.7z");
+        let progress_path = tmp.path().join("1.json");
+        fs::write(&dest_path, vec![0u8; 100])?;
+
+        let progress = |progress_bytes| DownloadProgress {
+            file_name: "out.7z".to_string(),
+            progress_bytes,
+            total_bytes: 1000,
+            started_at: 1,
+            updated_at: 1,
+        };
+
+        // Act
+        fs::write(&progress_path, serde_json::to_string(&progress(40))?)?;
+        let behind = sut.resume_offset(&dest_path, &progress_path)?;
+
+        fs::write(&progress_path, serde_json::to_string(&progress(400))?)?;
+        let ahead = sut.resume_offset(&dest_path, &progress_path)?;
+
+        // Assert
+        assert_eq!(behind, 40);
+        assert_eq!(ahead, 100);
+
+        Ok(())
+    }
+
     #[test]
     fn tracking_file_should_return_correct_path() -> anyhow::Result<()> {
         // Arrange
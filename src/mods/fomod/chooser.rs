@@ -0,0 +1,199 @@
+use crate::{
+    mods::fomod::{
+        installer::Chooser,
+        types::{Group, GroupType, InstallStep, PluginType},
+    },
+    ui::prompt,
+};
+
+/// Lets the user make every FOMOD choice through the terminal prompts.
+pub struct PromptChooser;
+
+impl Chooser for PromptChooser {
+    fn choose(
+        &mut self,
+        step: &InstallStep,
+        group: &Group,
+        types: &[PluginType],
+    ) -> anyhow::Result<Vec<usize>> {
+        let usable: Vec<usize> = (0..group.plugins.len())
+            .filter(|i| types[*i] != PluginType::NotUsable)
+            .collect();
+
+        let labels: Vec<String> = usable
+            .iter()
+            .map(|i| match types[*i] {
+                PluginType::Required => format!("{} (required)", group.plugins[*i]),
+                PluginType::Recommended => format!("{} (recommended)", group.plugins[*i]),
+                _ => group.plugins[*i].to_string(),
+            })
+            .collect();
+        let preselected: Vec<bool> = usable
+            .iter()
+            .map(|i| matches!(types[*i], PluginType::Required | PluginType::Recommended))
+            .collect();
+
+        let prompt_text = if step.name.is_empty() {
+            group.name.to_string()
+        } else {
+            format!("{} - {}", step.name, group.name)
+        };
+
+        match group.group_type {
+            GroupType::SelectAll => Ok(usable),
+            GroupType::SelectExactlyOne if usable.len() <= 1 => Ok(usable),
+            GroupType::SelectExactlyOne => {
+                let default = preselected.iter().position(|p| *p).unwrap_or(0);
+                let index = prompt::select_index(&prompt_text, &labels, default)?;
+                Ok(vec![usable[index]])
+            }
+            GroupType::SelectAtMostOne => {
+                let mut options = labels;
+                options.push("None".to_string());
+
+                let default = preselected
+                    .iter()
+                    .position(|p| *p)
+                    .unwrap_or(options.len() - 1);
+                let index = prompt::select_index(&prompt_text, &options, default)?;
+                Ok(usable.get(index).map(|i| vec![*i]).unwrap_or_default())
+            }
+            GroupType::SelectAtLeastOne | GroupType::SelectAny => {
+                let allow_empty = group.group_type == GroupType::SelectAny;
+                let selection = prompt::select_multiple_indices(
+                    &prompt_text,
+                    &labels,
+                    &preselected,
+                    allow_empty,
+                )?;
+                Ok(selection.into_iter().map(|i| usable[i]).collect())
+            }
+        }
+    }
+}
+
+/// Picks the options the mod author marked as required or recommended without asking. Falls back
+/// to the first usable option when a group needs at least one selection.
+pub struct DefaultChooser;
+
+impl Chooser for DefaultChooser {
+    fn choose(
+        &mut self,
+        _step: &InstallStep,
+        group: &Group,
+        types: &[PluginType],
+    ) -> anyhow::Result<Vec<usize>> {
+        let usable: Vec<usize> = (0..group.plugins.len())
+            .filter(|i| types[*i] != PluginType::NotUsable)
+            .collect();
+        let preselected: Vec<usize> = usable
+            .iter()
+            .copied()
+            .filter(|i| matches!(types[*i], PluginType::Required | PluginType::Recommended))
+            .collect();
+
+        let selection = match group.group_type {
+            GroupType::SelectAll => usable,
+            GroupType::SelectAny => preselected,
+            GroupType::SelectAtMostOne => preselected.into_iter().take(1).collect(),
+            GroupType::SelectExactlyOne => preselected
+                .first()
+                .or(usable.first())
+                .map(|i| vec![*i])
+                .unwrap_or_default(),
+            GroupType::SelectAtLeastOne if preselected.is_empty() => {
+                usable.into_iter().take(1).collect()
+            }
+            GroupType::SelectAtLeastOne => preselected,
+        };
+
+        Ok(selection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::fomod::types::{Plugin, TypeDescriptor};
+
+    fn group(group_type: GroupType, amount: usize) -> Group {
+        Group {
+            name: "Group".to_string(),
+            group_type,
+            plugins: (0..amount)
+                .map(|i| Plugin {
+                    name: format!("Option {}", i),
+                    description: String::new(),
+                    files: vec![],
+                    condition_flags: vec![],
+                    type_descriptor: TypeDescriptor {
+                        default: PluginType::Optional,
+                        patterns: vec![],
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn step() -> InstallStep {
+        InstallStep {
+            name: "Step".to_string(),
+            visible: None,
+            groups: vec![],
+        }
+    }
+
+    #[test]
+    fn default_chooser_should_pick_recommended_options() -> anyhow::Result<()> {
+        // Arrange
+        let group = group(GroupType::SelectAny, 3);
+        let types = [
+            PluginType::Recommended,
+            PluginType::Optional,
+            PluginType::Required,
+        ];
+
+        // Act
+        let actual = DefaultChooser.choose(&step(), &group, &types)?;
+
+        // Assert
+        assert_eq!(actual, vec![0, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn default_chooser_should_pick_first_usable_when_one_is_required() -> anyhow::Result<()> {
+        // Arrange
+        let group = group(GroupType::SelectExactlyOne, 3);
+        let types = [
+            PluginType::NotUsable,
+            PluginType::Optional,
+            PluginType::Optional,
+        ];
+
+        // Act
+        let actual = DefaultChooser.choose(&step(), &group, &types)?;
+
+        // Assert
+        assert_eq!(actual, vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn default_chooser_should_pick_nothing_when_none_recommended_and_optional() -> anyhow::Result<()>
+    {
+        // Arrange
+        let group = group(GroupType::SelectAtMostOne, 2);
+        let types = [PluginType::Optional, PluginType::Optional];
+
+        // Act
+        let actual = DefaultChooser.choose(&step(), &group, &types)?;
+
+        // Assert
+        assert!(actual.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,613 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use walkdir::WalkDir;
+
+use crate::mods::fomod::types::{
+    Dependency, FileInstall, FileState, Group, GroupType, InstallStep, ModuleConfig, Operator,
+    PluginType,
+};
+
+const FOMOD_DIR: &str = "fomod";
+const MODULE_CONFIG: &str = "moduleconfig.xml";
+
+/// Makes the choices for every group the FOMOD installer walks through.
+pub trait Chooser {
+    /// Returns the indices of the selected plugins in `group`. `types` holds the evaluated type of each plugin.
+    fn choose(
+        &mut self,
+        step: &InstallStep,
+        group: &Group,
+        types: &[PluginType],
+    ) -> anyhow::Result<Vec<usize>>;
+}
+
+struct InstallState<'a> {
+    flags: HashMap<String, String>,
+    data_files: &'a HashSet<String>,
+}
+
+impl InstallState<'_> {
+    fn is_satisfied(&self, dependency: &Dependency) -> bool {
+        match dependency {
+            Dependency::Flag { name, value } => {
+                self.flags.get(name).map(String::as_str).unwrap_or_default() == value
+            }
+            Dependency::File { file, state } => {
+                // Moma doesn't track active plugins, so a present file counts as active.
+                let present = self.data_files.contains(&file.to_lowercase());
+                match state {
+                    FileState::Active => present,
+                    FileState::Inactive => false,
+                    FileState::Missing => !present,
+                }
+            }
+            Dependency::Unchecked => true,
+            Dependency::Composite { operator, children } => match operator {
+                Operator::And => children.iter().all(|c| self.is_satisfied(c)),
+                Operator::Or => {
+                    children.is_empty() || children.iter().any(|c| self.is_satisfied(c))
+                }
+            },
+        }
+    }
+}
+
+/// Looks for `fomod/ModuleConfig.xml` in the extracted archive, or one folder deeper for wrapped archives.
+pub fn find_module_config(extracted_dir: &Path) -> anyhow::Result<Option<PathBuf>> {
+    let mut candidates = vec![extracted_dir.to_path_buf()];
+
+    for entry in fs::read_dir(extracted_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            candidates.push(entry.path());
+        }
+    }
+
+    for candidate in candidates {
+        if let Some(config) = resolve_case_insensitive(&candidate, Path::new(FOMOD_DIR))
+            .and_then(|fomod_dir| resolve_case_insensitive(&fomod_dir, Path::new(MODULE_CONFIG)))
+        {
+            return Ok(Some(config));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Walks the install steps with the given chooser and returns every file to install, ordered by priority.
+pub fn plan(
+    config: &ModuleConfig,
+    chooser: &mut dyn Chooser,
+    data_files: &HashSet<String>,
+) -> anyhow::Result<Vec<FileInstall>> {
+    let mut state = InstallState {
+        flags: HashMap::new(),
+        data_files,
+    };
+
+    if config
+        .module_dependencies
+        .as_ref()
+        .is_some_and(|d| !state.is_satisfied(d))
+    {
+        bail!("Requirements for '{}' are not met", config.name);
+    }
+
+    let mut files = config.required_files.clone();
+
+    for step in &config.steps {
+        if step
+            .visible
+            .as_ref()
+            .is_some_and(|v| !state.is_satisfied(v))
+        {
+            continue;
+        }
+
+        for group in &step.groups {
+            let types: Vec<PluginType> = group
+                .plugins
+                .iter()
+                .map(|p| {
+                    p.type_descriptor
+                        .patterns
+                        .iter()
+                        .find(|(dependency, _)| state.is_satisfied(dependency))
+                        .map(|(_, plugin_type)| *plugin_type)
+                        .unwrap_or(p.type_descriptor.default)
+                })
+                .collect();
+
+            let required: Vec<usize> = (0..types.len())
+                .filter(|i| types[*i] == PluginType::Required)
+                .collect();
+            let single_choice = matches!(
+                group.group_type,
+                GroupType::SelectExactlyOne | GroupType::SelectAtMostOne
+            );
+
+            // A required plugin is the only valid pick of a single choice group, so there is
+            // nothing to ask. Other groups get the required plugins on top of the choice.
+            let mut selected = match group.group_type {
+                GroupType::SelectAll => (0..group.plugins.len()).collect(),
+                _ if single_choice && !required.is_empty() => vec![required[0]],
+                _ => chooser.choose(step, group, &types)?,
+            };
+            if !single_choice {
+                selected.extend(required);
+            }
+            selected.retain(|i| types.get(*i).is_some_and(|t| *t != PluginType::NotUsable));
+            selected.sort_unstable();
+            selected.dedup();
+
+            validate_selection(group, selected.len())?;
+
+            for (i, plugin) in group.plugins.iter().enumerate() {
+                if selected.contains(&i) {
+                    for (name, value) in &plugin.condition_flags {
+                        state.flags.insert(name.clone(), value.clone());
+                    }
+                    files.extend(plugin.files.iter().cloned());
+                } else {
+                    files.extend(
+                        plugin
+                            .files
+                            .iter()
+                            .filter(|f| {
+                                f.always_install
+                                    || (f.install_if_usable && types[i] != PluginType::NotUsable)
+                            })
+                            .cloned(),
+                    );
+                }
+            }
+        }
+    }
+
+    for conditional in &config.conditional_installs {
+        if state.is_satisfied(&conditional.dependency) {
+            files.extend(conditional.files.iter().cloned());
+        }
+    }
+
+    // Stable sort, files with equal priority keep their document order.
+    files.sort_by_key(|f| f.priority);
+
+    Ok(files)
+}
+
+/// Copies the planned files from the extracted archive into the target directory.
+pub fn apply(files: &[FileInstall], source_root: &Path, target_root: &Path) -> anyhow::Result<()> {
+    for file in files {
+        let source = resolve_case_insensitive(source_root, &contained_path(&file.source)?)
+            .ok_or_else(|| anyhow::anyhow!("FOMOD references missing path '{}'", file.source))?;
+        let destination = target_root.join(contained_path(&file.destination)?);
+
+        if file.is_folder {
+            for entry in WalkDir::new(&source)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.file_type().is_file())
+            {
+                let relative = entry.path().strip_prefix(&source)?;
+                copy_file(entry.path(), &destination.join(relative))?;
+            }
+        } else {
+            copy_file(&source, &destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn copy_file(from: &Path, to: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::copy(from, to)
+        .with_context(|| format!("Could not copy '{}' to '{}'", from.display(), to.display()))?;
+
+    Ok(())
+}
+
+fn validate_selection(group: &Group, amount: usize) -> anyhow::Result<()> {
+    let valid = match group.group_type {
+        GroupType::SelectExactlyOne => amount == 1,
+        GroupType::SelectAtMostOne => amount <= 1,
+        GroupType::SelectAtLeastOne => amount >= 1,
+        GroupType::SelectAll | GroupType::SelectAny => true,
+    };
+
+    if !valid {
+        bail!(
+            "Invalid selection for group '{}' ({:?})",
+            group.name,
+            group.group_type
+        );
+    }
+
+    Ok(())
+}
+
+/// Normalises a path from a FOMOD into one that stays inside the folder it is joined to.
+/// Archives are untrusted, so parent, root and prefix components are rejected instead of being
+/// allowed to reach files outside the mod.
+fn contained_path(path: &str) -> anyhow::Result<PathBuf> {
+    let normalised = path.replace('\\', "/");
+    let mut contained = PathBuf::new();
+
+    for component in Path::new(&normalised).components() {
+        match component {
+            Component::Normal(part) => contained.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => bail!(
+                "FOMOD path '{}' points outside the mod, refusing to install it",
+                path
+            ),
+        }
+    }
+
+    Ok(contained)
+}
+
+/// Resolves a relative path, ignoring the casing of every component.
+fn resolve_case_insensitive(root: &Path, relative: &Path) -> Option<PathBuf> {
+    let mut current = root.to_path_buf();
+
+    for component in relative.iter() {
+        let exact = current.join(component);
+        if exact.exists() {
+            current = exact;
+            continue;
+        }
+
+        let wanted = component.to_string_lossy().to_lowercase();
+        current = fs::read_dir(&current)
+            .ok()?
+            .filter_map(Result::ok)
+            .find(|e| e.file_name().to_string_lossy().to_lowercase() == wanted)?
+            .path();
+    }
+
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::fomod::parser::parse_module_config;
+    use tempfile::TempDir;
+
+    struct ScriptedChooser {
+        choices: Vec<Vec<usize>>,
+    }
+
+    impl Chooser for ScriptedChooser {
+        fn choose(
+            &mut self,
+            _step: &InstallStep,
+            _group: &Group,
+            _types: &[PluginType],
+        ) -> anyhow::Result<Vec<usize>> {
+            Ok(self.choices.remove(0))
+        }
+    }
+
+    const CONFIG: &str = r#"<config>
+        <moduleName>Test</moduleName>
+        <requiredInstallFiles>
+            <file source="core.esp" destination="core.esp" />
+        </requiredInstallFiles>
+        <installSteps order="Explicit">
+            <installStep name="Main">
+                <optionalFileGroups>
+                    <group name="Options" type="SelectExactlyOne">
+                        <plugins order="Explicit">
+                            <plugin name="A">
+                                <files><file source="a.esp" destination="option.esp" /></files>
+                                <conditionFlags><flag name="choice">a</flag></conditionFlags>
+                                <typeDescriptor><type name="Optional" /></typeDescriptor>
+                            </plugin>
+                            <plugin name="B">
+                                <files><file source="b.esp" destination="option.esp" /></files>
+                                <conditionFlags><flag name="choice">b</flag></conditionFlags>
+                                <typeDescriptor><type name="Optional" /></typeDescriptor>
+                            </plugin>
+                        </plugins>
+                    </group>
+                </optionalFileGroups>
+            </installStep>
+            <installStep name="Only for A">
+                <visible><flagDependency flag="choice" value="a" /></visible>
+                <optionalFileGroups>
+                    <group name="Extras" type="SelectAny">
+                        <plugins>
+                            <plugin name="Extra">
+                                <files><file source="extra.esp" /></files>
+                                <typeDescriptor><type name="Optional" /></typeDescriptor>
+                            </plugin>
+                        </plugins>
+                    </group>
+                </optionalFileGroups>
+            </installStep>
+        </installSteps>
+        <conditionalFileInstalls>
+            <patterns>
+                <pattern>
+                    <dependencies><flagDependency flag="choice" value="b" /></dependencies>
+                    <files><file source="b-patch.esp" priority="5" /></files>
+                </pattern>
+            </patterns>
+        </conditionalFileInstalls>
+    </config>"#;
+
+    fn sources(files: &[FileInstall]) -> Vec<&str> {
+        files.iter().map(|f| f.source.as_str()).collect()
+    }
+
+    #[test]
+    fn plan_should_follow_selected_options_and_visible_steps() -> anyhow::Result<()> {
+        // Arrange
+        let config = parse_module_config(CONFIG)?;
+        let mut chooser = ScriptedChooser {
+            choices: vec![vec![0], vec![0]],
+        };
+
+        // Act
+        let actual = plan(&config, &mut chooser, &HashSet::new())?;
+
+        // Assert
+        assert_eq!(sources(&actual), vec!["core.esp", "a.esp", "extra.esp"]);
+        assert!(chooser.choices.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_should_skip_invisible_steps_and_apply_conditional_installs() -> anyhow::Result<()> {
+        // Arrange
+        let config = parse_module_config(CONFIG)?;
+        let mut chooser = ScriptedChooser {
+            choices: vec![vec![1]],
+        };
+
+        // Act
+        let actual = plan(&config, &mut chooser, &HashSet::new())?;
+
+        // Assert
+        assert_eq!(sources(&actual), vec!["core.esp", "b.esp", "b-patch.esp"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_should_return_err_when_selection_violates_group_type() -> anyhow::Result<()> {
+        // Arrange
+        let config = parse_module_config(CONFIG)?;
+        let mut chooser = ScriptedChooser {
+            choices: vec![vec![0, 1]],
+        };
+
+        // Act
+        let result = plan(&config, &mut chooser, &HashSet::new());
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_should_always_include_required_and_exclude_not_usable_plugins() -> anyhow::Result<()> {
+        // Arrange
+        let xml = r#"<config><installSteps><installStep name="Step"><optionalFileGroups>
+            <group name="Group" type="SelectAny"><plugins order="Explicit">
+                <plugin name="Required">
+                    <files><file source="required.esp" /></files>
+                    <typeDescriptor><type name="Required" /></typeDescriptor>
+                </plugin>
+                <plugin name="Unusable">
+                    <files><file source="unusable.esp" /></files>
+                    <typeDescriptor>
+                        <dependencyType>
+                            <defaultType name="Optional" />
+                            <patterns><pattern>
+                                <dependencies><fileDependency file="Missing.esp" state="Missing" /></dependencies>
+                                <type name="NotUsable" />
+                            </pattern></patterns>
+                        </dependencyType>
+                    </typeDescriptor>
+                </plugin>
+            </plugins></group>
+        </optionalFileGroups></installStep></installSteps></config>"#;
+        let config = parse_module_config(xml)?;
+        let mut chooser = ScriptedChooser {
+            choices: vec![vec![1]],
+        };
+
+        // Act
+        let actual = plan(&config, &mut chooser, &HashSet::new())?;
+
+        // Assert
+        assert_eq!(sources(&actual), vec!["required.esp"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_should_pick_required_plugin_of_single_choice_groups() -> anyhow::Result<()> {
+        for group_type in ["SelectExactlyOne", "SelectAtMostOne"] {
+            // Arrange
+            let xml = format!(
+                r#"<config><installSteps><installStep name="Step"><optionalFileGroups>
+                <group name="Group" type="{}"><plugins order="Explicit">
+                    <plugin name="Optional">
+                        <files><file source="optional.esp" /></files>
+                        <typeDescriptor><type name="Optional" /></typeDescriptor>
+                    </plugin>
+                    <plugin name="Required">
+                        <files><file source="required.esp" /></files>
+                        <typeDescriptor><type name="Required" /></typeDescriptor>
+                    </plugin>
+                </plugins></group>
+                <group name="Other" type="SelectAny"><plugins order="Explicit">
+                    <plugin name="Extra">
+                        <files><file source="extra.esp" /></files>
+                        <typeDescriptor><type name="Optional" /></typeDescriptor>
+                    </plugin>
+                </plugins></group>
+            </optionalFileGroups></installStep></installSteps></config>"#,
+                group_type
+            );
+            let config = parse_module_config(&xml)?;
+            let mut chooser = ScriptedChooser {
+                choices: vec![vec![0]],
+            };
+
+            // Act
+            let actual = plan(&config, &mut chooser, &HashSet::new())?;
+
+            // Assert
+            assert_eq!(
+                sources(&actual),
+                vec!["required.esp", "extra.esp"],
+                "{}",
+                group_type
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_copy_files_and_folders_ignoring_case() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let source_root = tmp_dir.path().join("source");
+        let target_root = tmp_dir.path().join("target");
+
+        fs::create_dir_all(source_root.join("Options/Textures"))?;
+        fs::write(source_root.join("Options/Textures/a.dds"), "texture")?;
+        fs::write(source_root.join("Plugin.esp"), "plugin")?;
+
+        let files = vec![
+            FileInstall {
+                source: "options/textures".to_string(),
+                destination: "textures".to_string(),
+                is_folder: true,
+                priority: 0,
+                always_install: false,
+                install_if_usable: false,
+            },
+            FileInstall {
+                source: "plugin.esp".to_string(),
+                destination: "renamed.esp".to_string(),
+                is_folder: false,
+                priority: 0,
+                always_install: false,
+                install_if_usable: false,
+            },
+        ];
+
+        // Act
+        let result = apply(&files, &source_root, &target_root);
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(
+            fs::read_to_string(target_root.join("textures/a.dds"))?,
+            "texture"
+        );
+        assert_eq!(
+            fs::read_to_string(target_root.join("renamed.esp"))?,
+            "plugin"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_module_config_should_find_config_in_wrapped_archive() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let fomod_dir = tmp_dir.path().join("Wrapper/FOMOD");
+        fs::create_dir_all(&fomod_dir)?;
+        fs::write(fomod_dir.join("ModuleConfig.xml"), "<config />")?;
+
+        // Act
+        let actual = find_module_config(tmp_dir.path())?;
+
+        // Assert
+        assert_eq!(actual, Some(fomod_dir.join("ModuleConfig.xml")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_module_config_should_return_none_when_no_fomod_present() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        fs::create_dir_all(tmp_dir.path().join("textures"))?;
+
+        // Act
+        let actual = find_module_config(tmp_dir.path())?;
+
+        // Assert
+        assert!(actual.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_return_err_when_path_points_outside_the_mod() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let source_root = tmp_dir.path().join("source");
+        let target_root = tmp_dir.path().join("target");
+
+        fs::create_dir_all(&source_root)?;
+        fs::write(source_root.join("Plugin.esp"), "plugin")?;
+        fs::write(tmp_dir.path().join("secret.txt"), "secret")?;
+
+        let file = |source: &str, destination: &str| FileInstall {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            is_folder: false,
+            priority: 0,
+            always_install: false,
+            install_if_usable: false,
+        };
+
+        // Act
+        let escaping_destination = apply(
+            &[file("plugin.esp", "../escape.esp")],
+            &source_root,
+            &target_root,
+        );
+        let absolute_destination = apply(
+            &[file("plugin.esp", "/tmp/escape.esp")],
+            &source_root,
+            &target_root,
+        );
+        let escaping_source = apply(
+            &[file("..\\secret.txt", "secret.txt")],
+            &source_root,
+            &target_root,
+        );
+
+        // Assert
+        assert!(escaping_destination.is_err());
+        assert!(absolute_destination.is_err());
+        assert!(escaping_source.is_err());
+        assert!(!tmp_dir.path().join("escape.esp").exists());
+        assert!(!target_root.join("secret.txt").exists());
+
+        Ok(())
+    }
+}
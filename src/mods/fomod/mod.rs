@@ -0,0 +1,12 @@
+mod chooser;
+mod installer;
+mod parser;
+mod types;
+
+pub use chooser::{DefaultChooser, PromptChooser};
+pub use installer::{Chooser, apply, find_module_config, plan};
+pub use parser::{parse_module_config, parse_module_config_file};
+pub use types::{
+    ConditionalInstall, Dependency, FileInstall, FileState, Group, GroupType, InstallStep,
+    ModuleConfig, Operator, Plugin, PluginType, TypeDescriptor,
+};
@@ -0,0 +1,489 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, bail};
+use roxmltree::{Document, Node};
+
+use crate::mods::fomod::types::{
+    ConditionalInstall, Dependency, FileInstall, FileState, Group, GroupType, InstallStep,
+    ModuleConfig, Operator, Plugin, PluginType, TypeDescriptor,
+};
+
+pub fn parse_module_config_file(path: &Path) -> anyhow::Result<ModuleConfig> {
+    let bytes = fs::read(path).with_context(|| format!("Could not read '{}'", path.display()))?;
+    let contents =
+        decode(&bytes).with_context(|| format!("Could not decode '{}'", path.display()))?;
+
+    parse_module_config(&contents)
+        .with_context(|| format!("Could not parse FOMOD config '{}'", path.display()))
+}
+
+pub fn parse_module_config(xml: &str) -> anyhow::Result<ModuleConfig> {
+    let document = Document::parse(xml)?;
+    let root = document.root_element();
+
+    if root.tag_name().name() != "config" {
+        bail!("Expected <config> as root element");
+    }
+
+    let name = child(root, "moduleName")
+        .and_then(|n| n.text())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    let module_dependencies = child(root, "moduleDependencies").map(parse_composite);
+
+    let required_files = child(root, "requiredInstallFiles")
+        .map(parse_file_list)
+        .transpose()?
+        .unwrap_or_default();
+
+    let mut steps = match child(root, "installSteps") {
+        Some(node) => children(node, "installStep")
+            .map(parse_step)
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => vec![],
+    };
+    if let Some(node) = child(root, "installSteps") {
+        sort_by_order(node, &mut steps, |s| &s.name);
+    }
+
+    let conditional_installs =
+        match child(root, "conditionalFileInstalls").and_then(|n| child(n, "patterns")) {
+            Some(patterns) => children(patterns, "pattern")
+                .map(|pattern| {
+                    Ok(ConditionalInstall {
+                        dependency: child(pattern, "dependencies")
+                            .map(parse_composite)
+                            .unwrap_or(Dependency::Unchecked),
+                        files: child(pattern, "files")
+                            .map(parse_file_list)
+                            .transpose()?
+                            .unwrap_or_default(),
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            None => vec![],
+        };
+
+    Ok(ModuleConfig {
+        name,
+        module_dependencies,
+        required_files,
+        steps,
+        conditional_installs,
+    })
+}
+
+/// FOMOD configs are regularly saved as UTF-16, so the BOM decides how the bytes are read.
+fn decode(bytes: &[u8]) -> anyhow::Result<String> {
+    let utf16 = |chunks: Vec<u16>| String::from_utf16(&chunks).context("Invalid UTF-16");
+
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => utf16(
+            rest.chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect(),
+        ),
+        [0xFE, 0xFF, rest @ ..] => utf16(
+            rest.chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect(),
+        ),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => Ok(String::from_utf8(rest.to_vec())?),
+        _ => Ok(String::from_utf8(bytes.to_vec())?),
+    }
+}
+
+fn parse_step(node: Node) -> anyhow::Result<InstallStep> {
+    let mut groups = match child(node, "optionalFileGroups") {
+        Some(groups_node) => children(groups_node, "group")
+            .map(parse_group)
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => vec![],
+    };
+    if let Some(groups_node) = child(node, "optionalFileGroups") {
+        sort_by_order(groups_node, &mut groups, |g| &g.name);
+    }
+
+    Ok(InstallStep {
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        visible: child(node, "visible").map(parse_composite),
+        groups,
+    })
+}
+
+fn parse_group(node: Node) -> anyhow::Result<Group> {
+    let type_name = node.attribute("type").unwrap_or("SelectAny");
+    let group_type = GroupType::from_name(type_name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown group type '{}'", type_name))?;
+
+    let mut plugins = match child(node, "plugins") {
+        Some(plugins_node) => children(plugins_node, "plugin")
+            .map(parse_plugin)
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => vec![],
+    };
+    if let Some(plugins_node) = child(node, "plugins") {
+        sort_by_order(plugins_node, &mut plugins, |p| &p.name);
+    }
+
+    Ok(Group {
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        group_type,
+        plugins,
+    })
+}
+
+fn parse_plugin(node: Node) -> anyhow::Result<Plugin> {
+    let files = child(node, "files")
+        .map(parse_file_list)
+        .transpose()?
+        .unwrap_or_default();
+
+    let condition_flags = child(node, "conditionFlags")
+        .map(|flags| {
+            children(flags, "flag")
+                .map(|flag| {
+                    (
+                        flag.attribute("name").unwrap_or_default().to_string(),
+                        flag.text().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let type_descriptor = match child(node, "typeDescriptor") {
+        Some(descriptor) => parse_type_descriptor(descriptor)?,
+        None => TypeDescriptor {
+            default: PluginType::Optional,
+            patterns: vec![],
+        },
+    };
+
+    Ok(Plugin {
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        description: child(node, "description")
+            .and_then(|d| d.text())
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        files,
+        condition_flags,
+        type_descriptor,
+    })
+}
+
+fn parse_type_descriptor(node: Node) -> anyhow::Result<TypeDescriptor> {
+    if let Some(plugin_type) = child(node, "type") {
+        return Ok(TypeDescriptor {
+            default: parse_plugin_type(plugin_type)?,
+            patterns: vec![],
+        });
+    }
+
+    let dependency_type = match child(node, "dependencyType") {
+        Some(d) => d,
+        None => bail!("Type descriptor has neither <type> nor <dependencyType>"),
+    };
+
+    let default = match child(dependency_type, "defaultType") {
+        Some(d) => parse_plugin_type(d)?,
+        None => PluginType::Optional,
+    };
+
+    let patterns = match child(dependency_type, "patterns") {
+        Some(patterns) => children(patterns, "pattern")
+            .map(|pattern| {
+                let dependency = child(pattern, "dependencies")
+                    .map(parse_composite)
+                    .unwrap_or(Dependency::Unchecked);
+                let plugin_type = child(pattern, "type")
+                    .map(parse_plugin_type)
+                    .transpose()?
+                    .unwrap_or(default);
+
+                Ok((dependency, plugin_type))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => vec![],
+    };
+
+    Ok(TypeDescriptor { default, patterns })
+}
+
+fn parse_plugin_type(node: Node) -> anyhow::Result<PluginType> {
+    let name = node.attribute("name").unwrap_or_default();
+    PluginType::from_name(name).ok_or_else(|| anyhow::anyhow!("Unknown plugin type '{}'", name))
+}
+
+/// Parses a composite dependency, these can both be `<dependencies>` and `<visible>` elements.
+fn parse_composite(node: Node) -> Dependency {
+    let operator = match node.attribute("operator") {
+        Some("Or") => Operator::Or,
+        _ => Operator::And,
+    };
+
+    let children = node
+        .children()
+        .filter(|n| n.is_element())
+        .map(|n| match n.tag_name().name() {
+            "flagDependency" => Dependency::Flag {
+                name: n.attribute("flag").unwrap_or_default().to_string(),
+                value: n.attribute("value").unwrap_or_default().to_string(),
+            },
+            "fileDependency" => Dependency::File {
+                file: normalize_path(n.attribute("file").unwrap_or_default()),
+                state: match n.attribute("state") {
+                    Some("Inactive") => FileState::Inactive,
+                    Some("Missing") => FileState::Missing,
+                    _ => FileState::Active,
+                },
+            },
+            "dependencies" => parse_composite(n),
+            _ => Dependency::Unchecked,
+        })
+        .collect();
+
+    Dependency::Composite { operator, children }
+}
+
+fn parse_file_list(node: Node) -> anyhow::Result<Vec<FileInstall>> {
+    node.children()
+        .filter(|n| n.is_element())
+        .filter(|n| matches!(n.tag_name().name(), "file" | "folder"))
+        .map(|n| {
+            let is_folder = n.tag_name().name() == "folder";
+            let source = match n.attribute("source") {
+                Some(s) => normalize_path(s),
+                None => bail!("<{}> is missing a source", n.tag_name().name()),
+            };
+
+            let destination = match n.attribute("destination") {
+                Some(d) => {
+                    let d = normalize_path(d);
+                    if d.is_empty() && !is_folder {
+                        // An empty destination for a file means the data root.
+                        source.rsplit('/').next().unwrap_or_default().to_string()
+                    } else {
+                        d
+                    }
+                }
+                None => source.clone(),
+            };
+
+            Ok(FileInstall {
+                source,
+                destination,
+                is_folder,
+                priority: n
+                    .attribute("priority")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(0),
+                always_install: n.attribute("alwaysInstall") == Some("true"),
+                install_if_usable: n.attribute("installIfUsable") == Some("true"),
+            })
+        })
+        .collect()
+}
+
+fn normalize_path(path: &str) -> String {
+    path.trim().replace('\\', "/").trim_matches('/').to_string()
+}
+
+/// Applies the FOMOD `order` attribute, which defaults to sorting ascending by name.
+fn sort_by_order<T>(node: Node, items: &mut [T], name: impl Fn(&T) -> &String) {
+    match node.attribute("order").unwrap_or("Ascending") {
+        "Explicit" => {}
+        "Descending" => items.sort_by(|a, b| name(b).cmp(name(a))),
+        _ => items.sort_by(|a, b| name(a).cmp(name(b))),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<config xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+    <moduleName>Test Mod</moduleName>
+    <requiredInstallFiles>
+        <folder source="Core" destination="" />
+    </requiredInstallFiles>
+    <installSteps order="Explicit">
+        <installStep name="Textures">
+            <optionalFileGroups>
+                <group name="Resolution" type="SelectExactlyOne">
+                    <plugins order="Explicit">
+                        <plugin name="4K">
+                            <description>Big textures</description>
+                            <files>
+                                <folder source="Options\4K\textures" destination="textures" priority="1" />
+                            </files>
+                            <conditionFlags>
+                                <flag name="resolution">4k</flag>
+                            </conditionFlags>
+                            <typeDescriptor>
+                                <type name="Optional" />
+                            </typeDescriptor>
+                        </plugin>
+                        <plugin name="2K">
+                            <files>
+                                <file source="Options\2K\tex.dds" />
+                            </files>
+                            <typeDescriptor>
+                                <dependencyType>
+                                    <defaultType name="Optional" />
+                                    <patterns>
+                                        <pattern>
+                                            <dependencies operator="Or">
+                                                <fileDependency file="Other.esp" state="Active" />
+                                            </dependencies>
+                                            <type name="Recommended" />
+                                        </pattern>
+                                    </patterns>
+                                </dependencyType>
+                            </typeDescriptor>
+                        </plugin>
+                    </plugins>
+                </group>
+            </optionalFileGroups>
+        </installStep>
+        <installStep name="Patches">
+            <visible>
+                <flagDependency flag="resolution" value="4k" />
+            </visible>
+            <optionalFileGroups>
+                <group name="Patches" type="SelectAny">
+                    <plugins>
+                        <plugin name="Patch">
+                            <typeDescriptor>
+                                <type name="Optional" />
+                            </typeDescriptor>
+                        </plugin>
+                    </plugins>
+                </group>
+            </optionalFileGroups>
+        </installStep>
+    </installSteps>
+    <conditionalFileInstalls>
+        <patterns>
+            <pattern>
+                <dependencies operator="And">
+                    <flagDependency flag="resolution" value="4k" />
+                </dependencies>
+                <files>
+                    <file source="Extra\extra.esp" destination="extra.esp" />
+                </files>
+            </pattern>
+        </patterns>
+    </conditionalFileInstalls>
+</config>"#;
+
+    #[test]
+    fn parse_module_config_should_parse_all_sections() -> anyhow::Result<()> {
+        // Act
+        let actual = parse_module_config(CONFIG)?;
+
+        // Assert
+        assert_eq!(actual.name, "Test Mod");
+        assert_eq!(actual.required_files.len(), 1);
+        assert!(actual.required_files[0].is_folder);
+        assert_eq!(actual.required_files[0].destination, "");
+
+        assert_eq!(actual.steps.len(), 2);
+        assert_eq!(actual.steps[0].name, "Textures");
+        assert!(actual.steps[1].visible.is_some());
+
+        let group = &actual.steps[0].groups[0];
+        assert_eq!(group.group_type, GroupType::SelectExactlyOne);
+        assert_eq!(group.plugins[0].name, "4K");
+        assert_eq!(group.plugins[0].description, "Big textures");
+        assert_eq!(
+            group.plugins[0].condition_flags,
+            vec![("resolution".to_string(), "4k".to_string())]
+        );
+        assert_eq!(group.plugins[0].files[0].source, "Options/4K/textures");
+        assert_eq!(group.plugins[0].files[0].priority, 1);
+        assert_eq!(group.plugins[1].type_descriptor.patterns.len(), 1);
+
+        assert_eq!(actual.conditional_installs.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_module_config_should_default_file_destination_to_source() -> anyhow::Result<()> {
+        // Act
+        let actual = parse_module_config(CONFIG)?;
+
+        // Assert
+        let file = &actual.steps[0].groups[0].plugins[1].files[0];
+        assert_eq!(file.source, "Options/2K/tex.dds");
+        assert_eq!(file.destination, "Options/2K/tex.dds");
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_module_config_should_sort_ascending_when_no_order_given() -> anyhow::Result<()> {
+        // Arrange
+        let xml = r#"<config><installSteps>
+            <installStep name="B" /><installStep name="A" />
+        </installSteps></config>"#;
+
+        // Act
+        let actual = parse_module_config(xml)?;
+
+        // Assert
+        let names: Vec<_> = actual.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "B"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_module_config_should_return_err_when_root_is_not_config() {
+        // Act
+        let result = parse_module_config("<module></module>");
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_module_config_file_should_decode_utf16() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("ModuleConfig.xml");
+
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "<config><moduleName>Wide</moduleName></config>".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        fs::write(&path, bytes)?;
+
+        // Act
+        let actual = parse_module_config_file(&path)?;
+
+        // Assert
+        assert_eq!(actual.name, "Wide");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::Display;
+
+/// Parsed representation of a `fomod/ModuleConfig.xml`.
+pub struct ModuleConfig {
+    pub name: String,
+    pub module_dependencies: Option<Dependency>,
+    pub required_files: Vec<FileInstall>,
+    pub steps: Vec<InstallStep>,
+    pub conditional_installs: Vec<ConditionalInstall>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileInstall {
+    /// Path relative to the folder containing the `fomod` directory.
+    pub source: String,
+
+    /// Path relative to the game's data directory.
+    pub destination: String,
+
+    pub is_folder: bool,
+    pub priority: i32,
+    pub always_install: bool,
+    pub install_if_usable: bool,
+}
+
+pub struct InstallStep {
+    pub name: String,
+    pub visible: Option<Dependency>,
+    pub groups: Vec<Group>,
+}
+
+pub struct Group {
+    pub name: String,
+    pub group_type: GroupType,
+    pub plugins: Vec<Plugin>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupType {
+    SelectAtLeastOne,
+    SelectAtMostOne,
+    SelectExactlyOne,
+    SelectAll,
+    SelectAny,
+}
+
+pub struct Plugin {
+    pub name: String,
+    pub description: String,
+    pub files: Vec<FileInstall>,
+    pub condition_flags: Vec<(String, String)>,
+    pub type_descriptor: TypeDescriptor,
+}
+
+pub struct TypeDescriptor {
+    pub default: PluginType,
+    pub patterns: Vec<(Dependency, PluginType)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PluginType {
+    Required,
+    Optional,
+    Recommended,
+    NotUsable,
+    CouldBeUsable,
+}
+
+pub struct ConditionalInstall {
+    pub dependency: Dependency,
+    pub files: Vec<FileInstall>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Dependency {
+    Flag {
+        name: String,
+        value: String,
+    },
+    File {
+        file: String,
+        state: FileState,
+    },
+    /// Dependencies moma can't verify, like game or mod manager versions. Always satisfied.
+    Unchecked,
+    Composite {
+        operator: Operator,
+        children: Vec<Dependency>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileState {
+    Active,
+    Inactive,
+    Missing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    And,
+    Or,
+}
+
+impl PluginType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Required" => Some(PluginType::Required),
+            "Optional" => Some(PluginType::Optional),
+            "Recommended" => Some(PluginType::Recommended),
+            "NotUsable" => Some(PluginType::NotUsable),
+            "CouldBeUsable" => Some(PluginType::CouldBeUsable),
+            _ => None,
+        }
+    }
+}
+
+impl GroupType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "SelectAtLeastOne" => Some(GroupType::SelectAtLeastOne),
+            "SelectAtMostOne" => Some(GroupType::SelectAtMostOne),
+            "SelectExactlyOne" => Some(GroupType::SelectExactlyOne),
+            "SelectAll" => Some(GroupType::SelectAll),
+            "SelectAny" => Some(GroupType::SelectAny),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Plugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+
+use crate::{
+    games::workspace::{self, Workspace},
+    types::{FileStatus, JournalEntry, Operation},
+};
+
+/// Bytes read from the end of the journal when looking for the last entry, doubled until a whole
+/// line fits.
+const TAIL_WINDOW: u64 = 1024;
+
+/// A change to the mod list, numbered and timestamped once it's appended to the journal.
+pub struct Change {
+    pub operation: Operation,
+    pub mod_uid: u64,
+    pub file_uid: u64,
+    pub old_status: FileStatus,
+    pub new_status: FileStatus,
+    pub reverts: Option<u64>,
+}
+
+/// Append-only record of every change made to the mod list, one JSON entry per line.
+#[derive(Clone)]
+pub struct JournalStore {
+    workspace: Workspace,
+}
+
+impl JournalStore {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> anyhow::Result<Vec<JournalEntry>> {
+        let journal_path = self.journal_path();
+
+        let content = match fs::read_to_string(&journal_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!(
+                        "Invalid journal entry on line {} of '{}'",
+                        i + 1,
+                        journal_path.display()
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Appends an entry. Callers must hold the mod list lock, so ids stay unique.
+    pub fn append(&self, change: Change) -> anyhow::Result<JournalEntry> {
+        let entry = JournalEntry {
+            id: self.last_id()? + 1,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            operation: change.operation,
+            mod_uid: change.mod_uid,
+            file_uid: change.file_uid,
+            old_status: change.old_status,
+            new_status: change.new_status,
+            reverts: change.reverts,
+        };
+
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.journal_path())
+            .with_context(|| "Failed to open journal for writing")?
+            .write_all(&line)?;
+
+        Ok(entry)
+    }
+
+    /// The most recent install or uninstall that hasn't been undone yet.
+    pub fn last_undoable(&self) -> anyhow::Result<Option<JournalEntry>> {
+        let entries = self.entries()?;
+        let reverted: Vec<u64> = entries.iter().filter_map(|e| e.reverts).collect();
+
+        let entry = entries.into_iter().rev().find(|e| {
+            e.reverts.is_none()
+                && !reverted.contains(&e.id)
+                && matches!(e.operation, Operation::Install | Operation::Uninstall)
+        });
+
+        Ok(entry)
+    }
+
+    /// Id of the newest entry, or 0 for an empty journal. Only the end of the file is read, the
+    /// journal grows with every change.
+    fn last_id(&self) -> anyhow::Result<u64> {
+        let mut file = match File::open(self.journal_path()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let len = file.metadata()?.len();
+
+        let mut window = TAIL_WINDOW;
+        loop {
+            let start = len.saturating_sub(window);
+            file.seek(SeekFrom::Start(start))?;
+            let mut tail = Vec::new();
+            file.read_to_end(&mut tail)?;
+
+            let tail = String::from_utf8_lossy(&tail);
+            let lines: Vec<_> = tail.lines().filter(|l| !l.trim().is_empty()).collect();
+
+            // The first line is cut off unless the window reaches the start of the file.
+            if let Some(last) = lines.last()
+                && (start == 0 || lines.len() > 1)
+            {
+                let entry: JournalEntry = serde_json::from_str(last).with_context(|| {
+                    format!(
+                        "Invalid last journal entry in '{}'",
+                        self.journal_path().display()
+                    )
+                })?;
+                return Ok(entry.id);
+            }
+
+            if start == 0 {
+                return Ok(0);
+            }
+            window *= 2;
+        }
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.workspace.work_dir().join(workspace::JOURNAL_FILE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config, GameConfig},
+        games::Game,
+    };
+    use tempfile::TempDir;
+
+    fn setup() -> anyhow::Result<(TempDir, JournalStore)> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = Config::test_with_config(tmp_dir.path().to_owned(), game_config);
+        let workspace = Workspace::new(&game, &config)?;
+        workspace.prepare_file_system()?;
+
+        Ok((tmp_dir, JournalStore::new(workspace)))
+    }
+
+    #[test]
+    fn append_should_number_entries_in_order() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, sut) = setup()?;
+
+        // Act
+        sut.append(Change {
+            operation: Operation::Add,
+            mod_uid: 1,
+            file_uid: 2,
+            old_status: FileStatus::Unknown,
+            new_status: FileStatus::Downloading,
+            reverts: None,
+        })?;
+        sut.append(Change {
+            operation: Operation::Stage,
+            mod_uid: 1,
+            file_uid: 2,
+            old_status: FileStatus::Downloading,
+            new_status: FileStatus::Downloaded,
+            reverts: None,
+        })?;
+
+        // Assert
+        let actual = sut.entries()?;
+        assert_eq!(actual.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(actual[1].operation, Operation::Stage);
+        assert_eq!(actual[1].new_status, FileStatus::Downloaded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn append_should_continue_numbering_when_entries_exceed_tail_window() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, sut) = setup()?;
+        for file_uid in 0..20 {
+            sut.append(Change {
+                operation: Operation::Add,
+                mod_uid: 1,
+                file_uid,
+                old_status: FileStatus::Unknown,
+                new_status: FileStatus::Downloading,
+                reverts: None,
+            })?;
+        }
+        sut.append(Change {
+            operation: Operation::Update,
+            mod_uid: 1,
+            file_uid: 0,
+            old_status: FileStatus::Downloading,
+            new_status: FileStatus::Failed("x".repeat(3 * TAIL_WINDOW as usize)),
+            reverts: None,
+        })?;
+
+        // Act
+        let actual = sut.append(Change {
+            operation: Operation::Update,
+            mod_uid: 1,
+            file_uid: 0,
+            old_status: FileStatus::Unknown,
+            new_status: FileStatus::Downloading,
+            reverts: None,
+        })?;
+
+        // Assert
+        assert_eq!(actual.id, 22);
+        assert_eq!(sut.entries()?.len(), 22);
+
+        Ok(())
+    }
+
+    #[test]
+    fn last_undoable_should_skip_reverted_entries() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, sut) = setup()?;
+        let installed = FileStatus::Installed;
+        let downloaded = FileStatus::Downloaded;
+        sut.append(Change {
+            operation: Operation::Install,
+            mod_uid: 1,
+            file_uid: 2,
+            old_status: downloaded.clone(),
+            new_status: installed.clone(),
+            reverts: None,
+        })?;
+        sut.append(Change {
+            operation: Operation::Install,
+            mod_uid: 3,
+            file_uid: 4,
+            old_status: downloaded.clone(),
+            new_status: installed.clone(),
+            reverts: None,
+        })?;
+        sut.append(Change {
+            operation: Operation::Uninstall,
+            mod_uid: 3,
+            file_uid: 4,
+            old_status: installed,
+            new_status: downloaded,
+            reverts: Some(2),
+        })?;
+
+        // Act
+        let actual = sut.last_undoable()?;
+
+        // Assert
+        assert_eq!(actual.map(|e| e.id), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn last_undoable_should_return_none_when_journal_missing() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, sut) = setup()?;
+
+        // Act
+        let actual = sut.last_undoable()?;
+
+        // Assert
+        assert!(actual.is_none());
+
+        Ok(())
+    }
+}
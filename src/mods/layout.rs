@@ -0,0 +1,249 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::games::Game;
+
+/// How deep moma looks through wrapper folders (e.g. `ModName/v1.2/`) before giving up.
+const MAX_WRAPPER_DEPTH: usize = 4;
+
+/// Folders that archives tend to ship next to the actual content.
+const IGNORED_FOLDERS: &[&str] = &["fomod", "__macosx", "docs", "readme"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ModLayout {
+    /// The folder holds content that belongs inside the game's data directory.
+    Data(PathBuf),
+
+    /// The folder mirrors the game's installation directory, including its data directory.
+    GameRoot(PathBuf),
+}
+
+/// Finds the folder in an extracted archive that should become the root of the installed mod.
+pub fn detect_layout(extracted_dir: &Path, game: &Game) -> anyhow::Result<ModLayout> {
+    let mut current = extracted_dir.to_path_buf();
+
+    for _ in 0..=MAX_WRAPPER_DEPTH {
+        if let Some(layout) = classify(&current, game)? {
+            return Ok(layout);
+        }
+
+        let sub_dirs = sub_dirs(&current)?;
+        if sub_dirs.len() != 1 {
+            break;
+        }
+
+        current = sub_dirs[0].clone();
+    }
+
+    log::warn!(
+        "Could not detect data root in '{}', assuming it's the data directory.",
+        extracted_dir.display()
+    );
+    Ok(ModLayout::Data(extracted_dir.to_path_buf()))
+}
+
+fn classify(dir: &Path, game: &Game) -> anyhow::Result<Option<ModLayout>> {
+    let mut is_data = false;
+    let mut is_root = false;
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+
+        if entry.file_type()?.is_dir() {
+            if name == game.data_dir().to_lowercase() {
+                return Ok(Some(ModLayout::GameRoot(dir.to_path_buf())));
+            }
+
+            is_data |= game.data_folders().contains(&name.as_str());
+        } else if let Some(ext) = Path::new(&name).extension().and_then(|e| e.to_str()) {
+            is_data |= game.data_extensions().contains(&ext);
+            is_root |= game.root_extensions().contains(&ext);
+        }
+    }
+
+    let layout = if is_data {
+        Some(ModLayout::Data(dir.to_path_buf()))
+    } else if is_root {
+        Some(ModLayout::GameRoot(dir.to_path_buf()))
+    } else {
+        None
+    };
+
+    Ok(layout)
+}
+
+fn sub_dirs(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+
+        if entry.file_type()?.is_dir() && !IGNORED_FOLDERS.contains(&name.as_str()) {
+            dirs.push(entry.path());
+        }
+    }
+
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup(files: &[&str]) -> anyhow::Result<TempDir> {
+        let tmp_dir = TempDir::new()?;
+
+        for file in files {
+            let path = tmp_dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, "")?;
+        }
+
+        Ok(tmp_dir)
+    }
+
+    #[test]
+    fn detect_layout_should_return_data_when_root_contains_plugins() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup(&["plugin.esp", "textures/a.dds"])?;
+
+        // Act
+        let actual = detect_layout(tmp_dir.path(), &Game::SkyrimSE)?;
+
+        // Assert
+        assert_eq!(actual, ModLayout::Data(tmp_dir.path().to_path_buf()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_layout_should_return_game_root_when_content_is_wrapped_in_data_folder()
+    -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup(&["data/plugin.esp"])?;
+
+        // Act
+        let actual = detect_layout(tmp_dir.path(), &Game::SkyrimSE)?;
+
+        // Assert
+        assert_eq!(actual, ModLayout::GameRoot(tmp_dir.path().to_path_buf()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_layout_should_descend_into_nested_wrapper_folders() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup(&[
+            "Mod Name/readme.txt",
+            "Mod Name/v1.2/Meshes/a.nif",
+            "Mod Name/v1.2/fomod/info.xml",
+        ])?;
+
+        // Act
+        let actual = detect_layout(tmp_dir.path(), &Game::SkyrimSE)?;
+
+        // Assert
+        assert_eq!(
+            actual,
+            ModLayout::Data(tmp_dir.path().join("Mod Name").join("v1.2"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_layout_should_return_game_root_when_root_contains_binaries() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup(&["wrapper/d3d11.dll", "wrapper/enblocal.ini"])?;
+
+        // Act
+        let actual = detect_layout(tmp_dir.path(), &Game::SkyrimSE)?;
+
+        // Assert
+        assert_eq!(actual, ModLayout::GameRoot(tmp_dir.path().join("wrapper")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_layout_should_fall_back_to_data_when_nothing_recognized() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup(&["first/a.txt", "second/b.txt"])?;
+
+        // Act
+        let actual = detect_layout(tmp_dir.path(), &Game::SkyrimSE)?;
+
+        // Assert
+        assert_eq!(actual, ModLayout::Data(tmp_dir.path().to_path_buf()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_layout_should_recognize_script_extenders_and_mods_of_each_game() -> anyhow::Result<()>
+    {
+        // Game, archive contents, whether the mod mirrors the game root and the folder it starts in.
+        let cases = [
+            (
+                Game::SkyrimLE,
+                vec![
+                    "skse_1_07_03/skse_loader.exe",
+                    "skse_1_07_03/Data/Scripts/a.pex",
+                ],
+                true,
+                "skse_1_07_03",
+            ),
+            (
+                Game::Fallout4,
+                vec![
+                    "f4se_0_06_23/f4se_loader.exe",
+                    "f4se_0_06_23/f4se_1_10_163.dll",
+                ],
+                true,
+                "f4se_0_06_23",
+            ),
+            (
+                Game::Fallout4,
+                vec!["Mod/Mod - Main.ba2", "Mod/Mod.esp"],
+                false,
+                "Mod",
+            ),
+            (
+                Game::FalloutNV,
+                vec!["nvse_loader.exe", "nvse_1_4.dll"],
+                true,
+                "",
+            ),
+            (
+                Game::FalloutNV,
+                vec!["NVSE/Plugins/a.dll", "menus/a.xml"],
+                false,
+                "",
+            ),
+        ];
+
+        for (game, files, is_game_root, root) in cases {
+            // Arrange
+            let tmp_dir = setup(&files)?;
+
+            // Act
+            let actual = detect_layout(tmp_dir.path(), &game)?;
+
+            // Assert
+            let root = tmp_dir.path().join(root);
+            let expected = match is_game_root {
+                true => ModLayout::GameRoot(root),
+                false => ModLayout::Data(root),
+            };
+            assert_eq!(actual, expected, "{} with {:?}", game, files);
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,9 @@
+pub mod conflicts;
 pub mod download_tracker;
 pub mod env_store;
+pub mod fomod;
+pub mod journal_store;
+pub mod layout;
 pub mod mod_list_store;
+pub mod plugin_list_store;
+pub mod sink_store;
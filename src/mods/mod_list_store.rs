@@ -1,40 +1,70 @@
+use md5::{Digest, Md5};
 use std::{
-    fs::{self, OpenOptions},
-    path::PathBuf,
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
 };
 
 use anyhow::{Context, bail};
 
 use crate::{
     games::workspace::{self, Workspace},
-    types::{FileStatus, Mod, ModArchive, ModList},
-    utils,
+    mods::{
+        fomod,
+        journal_store::{Change, JournalStore},
+        layout::{self, ModLayout},
+    },
+    sources::Source,
+    types::{
+        FileStatus, JournalEntry, LOCAL_UID_START, Mod, ModArchive, ModLayer, ModList, Operation,
+    },
+    utils::{
+        self,
+        fs::{FileLock, NormalizedDirs},
+    },
 };
 
 #[derive(Clone)]
 pub struct ModListStore {
     workspace: Workspace,
+    journal_store: JournalStore,
 }
 
 impl ModListStore {
     pub fn new(workspace: Workspace) -> Self {
-        Self { workspace }
+        Self {
+            journal_store: JournalStore::new(workspace.clone()),
+            workspace,
+        }
     }
 
     pub fn archive_download_dest(&self, file_name: &str) -> PathBuf {
         self.workspace.cache_dir().join(file_name)
     }
 
+    /// Reads the mod list. A file that can't be parsed is copied aside and reported instead of
+    /// being treated as empty, since the next write would otherwise wipe every entry.
     pub fn read(&self) -> anyhow::Result<ModList> {
-        let mod_list_path = self.workspace.work_dir().join(workspace::MOD_LIST_FILE);
+        let mod_list_path = self.mod_list_path();
 
-        let mod_list = match fs::read_to_string(mod_list_path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ModList::default(),
+        let content = match fs::read_to_string(&mod_list_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ModList::default()),
             Err(e) => return Err(e.into()),
         };
 
-        Ok(mod_list)
+        match serde_json::from_str(&content) {
+            Ok(mod_list) => Ok(mod_list),
+            Err(e) => {
+                let backup_path = self.back_up_corrupt(&content)?;
+                bail!(
+                    "Mod list '{}' is corrupt ({}). A copy was saved to '{}', fix or remove the file to continue.",
+                    mod_list_path.display(),
+                    e,
+                    backup_path.display()
+                )
+            }
+        }
     }
 
     pub fn stage_archive(&self, m: &Mod, archive: &ModArchive) -> anyhow::Result<()> {
@@ -58,7 +88,7 @@ impl ModListStore {
         fs::rename(&archive_path, &target_path)
             .with_context(|| "Could not move archive to staging directory.")?;
 
-        self.update_archive(&m.uid, &archive.file_uid, |a| {
+        self.change_archive(Operation::Stage, None, m.uid, archive.file_uid, |a| {
             a.status = FileStatus::Downloaded;
             a.archive_path = Some(target_path);
         })?;
@@ -66,86 +96,666 @@ impl ModListStore {
         Ok(())
     }
 
-    pub fn install_archive(&self, m: &Mod, archive: &ModArchive) -> anyhow::Result<()> {
-        let mods_dir = self.workspace.mods_dir();
+    pub fn install_archive(
+        &self,
+        m: &Mod,
+        archive: &ModArchive,
+        chooser: &mut dyn fomod::Chooser,
+    ) -> anyhow::Result<NormalizedDirs> {
+        self.install(m, archive, chooser, None)
+    }
 
-        // TODO: Detect if folder needs to be flattened
-        // TODO: Detect FOMOD
+    fn install(
+        &self,
+        m: &Mod,
+        archive: &ModArchive,
+        chooser: &mut dyn fomod::Chooser,
+        reverts: Option<u64>,
+    ) -> anyhow::Result<NormalizedDirs> {
         let archive_path = match &archive.archive_path {
             Some(a) => a,
             None => bail!("No archive path found for: '{}'", archive.file_name),
         };
 
-        utils::fs::extract_archive(
-            &archive_path,
-            &mods_dir.join(archive.file_uid.to_string()),
-            false,
-        )?;
+        if !archive_path.exists() {
+            bail!(
+                "Staged archive '{}' no longer exists, download it again to install it.",
+                archive_path.display()
+            );
+        }
+
+        let extract_dir = self
+            .workspace
+            .extract_dir()
+            .join(archive.file_uid.to_string());
+        if extract_dir.exists() {
+            fs::remove_dir_all(&extract_dir)?;
+        }
+
+        utils::fs::extract_archive(archive_path, &extract_dir, false)?;
+
+        let install_dir = self.workspace.mods_dir().join(archive.file_uid.to_string());
+        if install_dir.exists() {
+            fs::remove_dir_all(&install_dir)?;
+        }
 
-        self.update_archive(&m.uid, &archive.file_uid, |a| {
+        let result = self.install_extracted(&extract_dir, &install_dir, chooser);
+
+        if extract_dir.exists() {
+            fs::remove_dir_all(&extract_dir)?;
+        }
+        result?;
+
+        let normalized = self.normalize_dir_case(&install_dir)?;
+
+        self.change_archive(Operation::Install, reverts, m.uid, archive.file_uid, |a| {
             a.status = FileStatus::Installed
         })?;
 
+        self.modify(|mod_list| {
+            let dir_name = archive.file_uid.to_string();
+            if !mod_list.priority.contains(&dir_name) {
+                mod_list.priority.push(dir_name);
+            }
+            Ok(())
+        })?;
+
+        Ok(normalized)
+    }
+
+    /// Folds the directory names in an installed mod to the game's canonical casing, so overlayfs
+    /// merges them with the same directories from the game and other mods.
+    pub fn normalize_dir_case(&self, mod_dir: &Path) -> anyhow::Result<NormalizedDirs> {
+        let game = self.workspace.game();
+        let game_dir = self.workspace.game_dir();
+        utils::fs::normalize_dir_case(mod_dir, &|p| game.canonical_dir_name(&game_dir, p))
+    }
+
+    fn install_extracted(
+        &self,
+        extract_dir: &Path,
+        install_dir: &Path,
+        chooser: &mut dyn fomod::Chooser,
+    ) -> anyhow::Result<()> {
+        let module_config = match fomod::find_module_config(extract_dir)? {
+            Some(path) => path,
+            None => {
+                let (source, destination) =
+                    match layout::detect_layout(extract_dir, self.workspace.game())? {
+                        ModLayout::Data(root) => {
+                            (root, install_dir.join(self.workspace.game().data_dir()))
+                        }
+                        ModLayout::GameRoot(root) => (root, install_dir.to_path_buf()),
+                    };
+
+                if let Some(parent) = destination.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                fs::rename(&source, &destination)
+                    .with_context(|| "Could not move extracted archive to mods directory.")?;
+                return Ok(());
+            }
+        };
+
+        let config = fomod::parse_module_config_file(&module_config)?;
+        let files = fomod::plan(&config, chooser, &self.data_file_names()?)?;
+
+        // Paths in a FOMOD are relative to the folder containing the `fomod` directory.
+        let source_root = module_config
+            .parent()
+            .and_then(Path::parent)
+            .ok_or_else(|| anyhow::anyhow!("Invalid FOMOD location"))?;
+
+        fomod::apply(
+            &files,
+            source_root,
+            &install_dir.join(self.workspace.game().data_dir()),
+        )
+    }
+
+    /// Lower cased names of all files directly in the game's and installed mods' data directories.
+    fn data_file_names(&self) -> anyhow::Result<HashSet<String>> {
+        let data_dir = self.workspace.game().data_dir();
+        let mut dirs = vec![self.workspace.game_dir().join(data_dir)];
+
+        if self.workspace.mods_dir().exists() {
+            for entry in fs::read_dir(self.workspace.mods_dir())? {
+                dirs.push(entry?.path().join(data_dir));
+            }
+        }
+
+        let names = dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(Result::ok))
+            .map(|e| e.file_name().to_string_lossy().to_lowercase())
+            .collect();
+
+        Ok(names)
+    }
+
+    pub fn uninstall_archive(&self, m: &Mod, archive: &ModArchive) -> anyhow::Result<()> {
+        self.uninstall(m, archive, None)
+    }
+
+    fn uninstall(&self, m: &Mod, archive: &ModArchive, reverts: Option<u64>) -> anyhow::Result<()> {
+        let install_dir = self.workspace.mods_dir().join(archive.file_uid.to_string());
+
+        if install_dir.exists() {
+            fs::remove_dir_all(&install_dir).with_context(|| {
+                format!(
+                    "Could not remove installed files at '{}'",
+                    install_dir.display()
+                )
+            })?;
+        }
+
+        self.change_archive(
+            Operation::Uninstall,
+            reverts,
+            m.uid,
+            archive.file_uid,
+            |a| a.status = FileStatus::Downloaded,
+        )?;
+
         Ok(())
     }
 
-    pub fn add_archive(&self, mod_to_add_to: &Mod, archive: ModArchive) -> anyhow::Result<()> {
-        let mut mod_list = self.read()?;
+    /// Every recorded change to the mod list, oldest first.
+    pub fn history(&self) -> anyhow::Result<Vec<JournalEntry>> {
+        self.journal_store.entries()
+    }
 
-        if let Some(existing_mod) = mod_list
+    /// Reverses the most recent install or uninstall that hasn't been undone yet. Undoing an
+    /// uninstall installs the staged archive again. Returns the entry that was reverted.
+    pub fn undo(&self, chooser: &mut dyn fomod::Chooser) -> anyhow::Result<Option<JournalEntry>> {
+        let Some(entry) = self.journal_store.last_undoable()? else {
+            return Ok(None);
+        };
+
+        let mod_list = self.read()?;
+        let found = mod_list
             .mods
-            .iter_mut()
-            .find(|m| m.uid == mod_to_add_to.uid)
-        {
-            let already_exists = existing_mod
+            .iter()
+            .find(|m| m.uid == entry.mod_uid)
+            .and_then(|m| {
+                m.archives
+                    .iter()
+                    .find(|a| a.file_uid == entry.file_uid)
+                    .map(|a| (m, a))
+            });
+        let Some((m, archive)) = found else {
+            bail!(
+                "Archive with file_uid {} under mod_uid {} is no longer in the mod list",
+                entry.file_uid,
+                entry.mod_uid
+            );
+        };
+
+        match entry.operation {
+            Operation::Install => self.uninstall(m, archive, Some(entry.id))?,
+            Operation::Uninstall => {
+                self.install(m, archive, chooser, Some(entry.id))?;
+            }
+            _ => bail!("Only installs and uninstalls can be undone"),
+        }
+
+        Ok(Some(entry))
+    }
+
+    pub fn remove_archive(
+        &self,
+        m: &Mod,
+        archive: &ModArchive,
+        delete_staged: bool,
+    ) -> anyhow::Result<()> {
+        let removed = self.modify_journaled(|mod_list| {
+            let existing_mod = match mod_list.mods.iter_mut().find(|e| e.uid == m.uid) {
+                Some(existing_mod) => existing_mod,
+                None => bail!("Mod with uid {} not found", m.uid),
+            };
+
+            let removed = match existing_mod
                 .archives
                 .iter()
-                .any(|a| a.file_uid == archive.file_uid);
+                .position(|a| a.file_uid == archive.file_uid)
+            {
+                Some(index) => existing_mod.archives.remove(index),
+                None => bail!(
+                    "Archive with file_uid {} under mod_uid {} not found",
+                    archive.file_uid,
+                    m.uid
+                ),
+            };
 
-            if !already_exists {
-                existing_mod.archives.push(archive);
+            if existing_mod.archives.is_empty() {
+                mod_list.mods.retain(|e| e.uid != m.uid);
             }
-        } else {
-            let new_mod = Mod {
-                uid: mod_to_add_to.uid,
-                name: mod_to_add_to.name.to_string(),
-                archives: vec![archive],
+
+            let change = Change {
+                operation: Operation::Remove,
+                mod_uid: m.uid,
+                file_uid: archive.file_uid,
+                old_status: removed.status.clone(),
+                new_status: FileStatus::Unknown,
+                reverts: None,
             };
 
-            mod_list.mods.push(new_mod);
+            Ok((removed, Some(change)))
+        })?;
+
+        if delete_staged {
+            if let Some(path) = removed.archive_path.as_ref().filter(|p| p.exists()) {
+                fs::remove_file(path)
+                    .with_context(|| format!("Could not delete archive '{}'", path.display()))?;
+            }
+
+            let mod_staging_dir = self.workspace.staging_dir().join(m.uid.to_string());
+            let archives_dir = mod_staging_dir.join(workspace::ARCHIVES);
+            if archives_dir.exists() && fs::read_dir(&archives_dir)?.next().is_none() {
+                fs::remove_dir_all(&mod_staging_dir)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// All installed mod folders, ordered from lowest to highest priority. Folders without a
+    /// stored priority are placed on top, sorted by name.
+    pub fn layers(&self) -> anyhow::Result<Vec<ModLayer>> {
+        let mod_list = self.read()?;
+        let mods_dir = self.workspace.mods_dir();
+
+        let mut layers = Vec::new();
+        for entry in fs::read_dir(&mods_dir)? {
+            let entry = entry?;
+            if !entry.metadata()?.is_dir() {
+                log::warn!("Not a directory, skipping: {}", entry.path().display());
+                continue;
+            }
+
+            let dir_name = entry.file_name().to_string_lossy().to_string();
+            let label = dir_name
+                .parse::<u64>()
+                .ok()
+                .and_then(|file_uid| {
+                    mod_list.mods.iter().find_map(|m| {
+                        m.archives
+                            .iter()
+                            .find(|a| a.file_uid == file_uid)
+                            .map(|a| format!("{} ({})", m.name, a.file_name))
+                    })
+                })
+                .unwrap_or_else(|| dir_name.clone());
+
+            layers.push(ModLayer {
+                dir_name,
+                label,
+                path: entry.path(),
+            });
         }
 
-        self.write(&mod_list)
+        layers.sort_by(|a, b| {
+            let position = |l: &ModLayer| {
+                mod_list
+                    .priority
+                    .iter()
+                    .position(|p| p == &l.dir_name)
+                    .unwrap_or(usize::MAX)
+            };
+            position(a)
+                .cmp(&position(b))
+                .then_with(|| a.dir_name.cmp(&b.dir_name))
+        });
+
+        Ok(layers)
+    }
+
+    pub fn set_priority(&self, layers: &[ModLayer]) -> anyhow::Result<()> {
+        self.modify(|mod_list| {
+            mod_list.priority = layers.iter().map(|l| l.dir_name.clone()).collect();
+            Ok(())
+        })
+    }
+
+    /// Moves the contents of `source_dir` into a new mod folder and registers it as an installed
+    /// mod with the highest priority.
+    pub fn add_local_mod(
+        &self,
+        name: &str,
+        file_name: &str,
+        source_dir: &Path,
+    ) -> anyhow::Result<ModLayer> {
+        self.modify_journaled(|mod_list| {
+            let uid = next_local_uid(mod_list);
+            let file_uid = uid + 1;
+
+            let dir_name = file_uid.to_string();
+            let install_dir = self.workspace.mods_dir().join(&dir_name);
+            fs::create_dir_all(&install_dir)?;
+
+            for entry in fs::read_dir(source_dir)? {
+                let entry = entry?;
+                fs::rename(entry.path(), install_dir.join(entry.file_name())).with_context(
+                    || {
+                        format!(
+                            "Could not move '{}' into mod folder",
+                            entry.path().display()
+                        )
+                    },
+                )?;
+            }
+
+            mod_list.mods.push(Mod {
+                uid,
+                name: name.to_string(),
+                archives: vec![ModArchive {
+                    file_uid,
+                    file_name: file_name.to_string(),
+                    archive_path: None,
+                    status: FileStatus::Installed,
+                    ..Default::default()
+                }],
+                source: Some(Source::Local),
+                ..Default::default()
+            });
+            mod_list.priority.push(dir_name.clone());
+
+            let change = Change {
+                operation: Operation::Add,
+                mod_uid: uid,
+                file_uid,
+                old_status: FileStatus::Unknown,
+                new_status: FileStatus::Installed,
+                reverts: None,
+            };
+
+            let layer = ModLayer {
+                label: format!("{} ({})", name, file_name),
+                dir_name,
+                path: install_dir,
+            };
+            Ok((layer, Some(change)))
+        })
+    }
+
+    /// Copies an archive from disk into the staging directory and registers it as a downloaded
+    /// local mod, ready to be installed.
+    pub fn add_local_archive(
+        &self,
+        name: &str,
+        archive_path: &Path,
+    ) -> anyhow::Result<(Mod, ModArchive)> {
+        let file_name = archive_path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .with_context(|| format!("'{}' is not a file", archive_path.display()))?;
+        let size = fs::metadata(archive_path)
+            .with_context(|| format!("Could not read '{}'", archive_path.display()))?
+            .len();
+
+        self.modify_journaled(|mod_list| {
+            let uid = next_local_uid(mod_list);
+            let file_uid = uid + 1;
+
+            let target = self
+                .workspace
+                .staging_dir()
+                .join(uid.to_string())
+                .join(workspace::ARCHIVES);
+            fs::create_dir_all(&target)?;
+
+            let target_path = target.join(&file_name);
+            fs::copy(archive_path, &target_path).with_context(|| {
+                format!(
+                    "Could not copy '{}' to the staging directory",
+                    archive_path.display()
+                )
+            })?;
+
+            let archive = ModArchive {
+                file_uid,
+                file_name: file_name.clone(),
+                archive_path: Some(target_path),
+                status: FileStatus::Downloaded,
+                size: Some(size),
+                ..Default::default()
+            };
+            let m = Mod {
+                uid,
+                name: name.to_string(),
+                archives: vec![archive.clone()],
+                source: Some(Source::Local),
+                ..Default::default()
+            };
+
+            mod_list.mods.push(m.clone());
+
+            let change = Change {
+                operation: Operation::Add,
+                mod_uid: uid,
+                file_uid,
+                old_status: FileStatus::Unknown,
+                new_status: FileStatus::Downloaded,
+                reverts: None,
+            };
+
+            Ok(((m, archive), Some(change)))
+        })
+    }
+
+    /// Adds an archive that has no uids on its mod source to `m`, or to a new mod when `m` isn't
+    /// in the mod list yet, giving both local uids.
+    pub fn add_local_source_archive(
+        &self,
+        m: &Mod,
+        archive: ModArchive,
+    ) -> anyhow::Result<(Mod, ModArchive)> {
+        self.modify_journaled(|mod_list| {
+            let uid = next_local_uid(mod_list);
+
+            let (m, archive) = match mod_list.mods.iter_mut().find(|e| e.uid == m.uid) {
+                Some(existing) => {
+                    let archive = ModArchive {
+                        file_uid: uid,
+                        ..archive
+                    };
+                    existing.update_metadata(m);
+                    existing.archives.push(archive.clone());
+
+                    (existing.clone(), archive)
+                }
+                None => {
+                    let archive = ModArchive {
+                        file_uid: uid + 1,
+                        ..archive
+                    };
+                    let new_mod = Mod {
+                        uid,
+                        archives: vec![archive.clone()],
+                        ..m.clone()
+                    };
+                    mod_list.mods.push(new_mod.clone());
+
+                    (new_mod, archive)
+                }
+            };
+
+            let change = Change {
+                operation: Operation::Add,
+                mod_uid: m.uid,
+                file_uid: archive.file_uid,
+                old_status: FileStatus::Unknown,
+                new_status: archive.status.clone(),
+                reverts: None,
+            };
+
+            Ok(((m, archive), Some(change)))
+        })
+    }
+
+    pub fn add_archive(&self, mod_to_add_to: &Mod, archive: ModArchive) -> anyhow::Result<()> {
+        self.modify_journaled(|mod_list| {
+            let (file_uid, status) = (archive.file_uid, archive.status.clone());
+
+            if let Some(existing_mod) = mod_list
+                .mods
+                .iter_mut()
+                .find(|m| m.uid == mod_to_add_to.uid)
+            {
+                existing_mod.update_metadata(mod_to_add_to);
+
+                let already_exists = existing_mod
+                    .archives
+                    .iter()
+                    .any(|a| a.file_uid == archive.file_uid);
+
+                if already_exists {
+                    return Ok(((), None));
+                }
+
+                existing_mod.archives.push(archive);
+            } else {
+                let new_mod = Mod {
+                    archives: vec![archive],
+                    ..mod_to_add_to.clone()
+                };
+
+                mod_list.mods.push(new_mod);
+            }
+
+            let change = Change {
+                operation: Operation::Add,
+                mod_uid: mod_to_add_to.uid,
+                file_uid,
+                old_status: FileStatus::Unknown,
+                new_status: status,
+                reverts: None,
+            };
+
+            Ok(((), Some(change)))
+        })
+    }
+
+    /// Marks an archive as downloading, adding it and its mod when they're new. The status is
+    /// checked and changed under one lock, so two jobs can't start the same download. Returns the
+    /// status from before, the archive is only claimed when that was unknown or failed.
+    pub fn claim_download(&self, m: &Mod, archive: &ModArchive) -> anyhow::Result<FileStatus> {
+        let claimed = ModArchive {
+            status: FileStatus::Downloading,
+            archive_path: Some(self.archive_download_dest(&archive.file_name)),
+            ..archive.clone()
+        };
+
+        self.modify_journaled(|mod_list| {
+            let index = match mod_list.mods.iter().position(|e| e.uid == m.uid) {
+                Some(index) => {
+                    mod_list.mods[index].update_metadata(m);
+                    index
+                }
+                None => {
+                    mod_list.mods.push(Mod {
+                        archives: vec![],
+                        ..m.clone()
+                    });
+                    mod_list.mods.len() - 1
+                }
+            };
+            let archives = &mut mod_list.mods[index].archives;
+
+            let (operation, old_status) =
+                match archives.iter_mut().find(|a| a.file_uid == claimed.file_uid) {
+                    Some(existing) => {
+                        if !matches!(existing.status, FileStatus::Unknown | FileStatus::Failed(_)) {
+                            return Ok((existing.status.clone(), None));
+                        }
+                        let old_status = existing.status.clone();
+                        *existing = claimed.clone();
+                        (Operation::Update, old_status)
+                    }
+                    None => {
+                        archives.push(claimed.clone());
+                        (Operation::Add, FileStatus::Unknown)
+                    }
+                };
+
+            let change = Change {
+                operation,
+                mod_uid: m.uid,
+                file_uid: claimed.file_uid,
+                old_status: old_status.clone(),
+                new_status: FileStatus::Downloading,
+                reverts: None,
+            };
+
+            Ok((old_status, Some(change)))
+        })
+    }
+
+    /// Applies `updater` to the metadata of a mod.
+    pub fn update_mod<F>(&self, mod_uid: u64, updater: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut Mod),
+    {
+        self.modify(|mod_list| {
+            let Some(m) = mod_list.mods.iter_mut().find(|m| m.uid == mod_uid) else {
+                bail!("Mod with uid {} not found", mod_uid);
+            };
+            updater(m);
+
+            Ok(())
+        })
     }
 
     pub fn update_archive<F>(&self, mod_uid: &u64, file_uid: &u64, updater: F) -> anyhow::Result<()>
     where
         F: FnOnce(&mut ModArchive),
     {
-        let mut mod_list = self.read()?;
+        self.change_archive(Operation::Update, None, *mod_uid, *file_uid, updater)
+    }
 
-        let found = mod_list
-            .mods
-            .iter_mut()
-            .find(|m| &m.uid == mod_uid)
-            .and_then(|m| {
-                m.archives
-                    .iter_mut()
-                    .find(|a| &a.file_uid == file_uid)
-                    .map(|archive| {
-                        updater(archive);
-                    })
-            });
+    /// Applies `updater` to an archive and records the status change in the journal.
+    fn change_archive<F>(
+        &self,
+        operation: Operation,
+        reverts: Option<u64>,
+        mod_uid: u64,
+        file_uid: u64,
+        updater: F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut ModArchive),
+    {
+        self.modify_journaled(|mod_list| {
+            let archive = mod_list
+                .mods
+                .iter_mut()
+                .find(|m| m.uid == mod_uid)
+                .and_then(|m| m.archives.iter_mut().find(|a| a.file_uid == file_uid));
+
+            let Some(archive) = archive else {
+                bail!(
+                    "Archive with file_uid {} under mod_uid {} not found",
+                    file_uid,
+                    mod_uid
+                );
+            };
 
-        if found.is_none() {
-            bail!(
-                "Archive with file_uid {} under mod_uid {} not found",
+            let old_status = archive.status.clone();
+            updater(archive);
+
+            let change = Change {
+                operation,
+                mod_uid,
                 file_uid,
-                mod_uid
-            );
-        }
+                old_status,
+                new_status: archive.status.clone(),
+                reverts,
+            };
 
-        self.write(&mod_list)
+            Ok(((), Some(change)))
+        })
     }
 
     pub fn get_archive_status(&self, mod_uid: u64, file_uid: u64) -> anyhow::Result<FileStatus> {
@@ -161,31 +771,119 @@ impl ModListStore {
         Ok(status.unwrap_or(FileStatus::Unknown))
     }
 
+    /// Reads, changes and writes the mod list while holding the lock, so concurrent processes
+    /// don't overwrite each other's changes.
+    fn modify<T, F>(&self, change: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&mut ModList) -> anyhow::Result<T>,
+    {
+        self.modify_journaled(|mod_list| Ok((change(mod_list)?, None)))
+    }
+
+    /// Like [`Self::modify`], and records the returned change in the journal once the mod list
+    /// is written, so a failed write doesn't leave an entry behind. The lock is still held then,
+    /// which keeps journal ids unique.
+    fn modify_journaled<T, F>(&self, change: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&mut ModList) -> anyhow::Result<(T, Option<Change>)>,
+    {
+        let _lock = self.lock()?;
+        let mut mod_list = self.read()?;
+        let (result, journal_change) = change(&mut mod_list)?;
+        self.write(&mod_list)?;
+
+        if let Some(journal_change) = journal_change {
+            self.journal_store.append(journal_change)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Every read-modify-write of the mod list has to hold this lock.
+    fn lock(&self) -> anyhow::Result<FileLock> {
+        FileLock::exclusive(&self.mod_list_path().with_extension("json.lock"))
+    }
+
     fn write(&self, mod_list: &ModList) -> anyhow::Result<()> {
-        let mod_list_path = self.workspace.work_dir().join(workspace::MOD_LIST_FILE);
+        let content = serde_json::to_vec_pretty(mod_list)?;
+        utils::fs::write_atomic(&self.mod_list_path(), &content)
+            .with_context(|| "Failed to write mod list file")
+    }
 
-        let mod_list_file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&mod_list_path)
-            .with_context(|| "Failed to open mod list file for writing")?;
+    /// Copies unparseable mod list contents next to the mod list, named after their hash so
+    /// repeated reads of the same file don't pile up copies.
+    fn back_up_corrupt(&self, content: &str) -> anyhow::Result<PathBuf> {
+        let hash: String = Md5::digest(content.as_bytes())
+            .iter()
+            .take(4)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let backup_path = self
+            .mod_list_path()
+            .with_extension(format!("json.corrupt-{}", hash));
+
+        if !backup_path.exists() {
+            fs::write(&backup_path, content)?;
+        }
 
-        serde_json::to_writer_pretty(mod_list_file, mod_list)?;
+        Ok(backup_path)
+    }
 
-        Ok(())
+    fn mod_list_path(&self) -> PathBuf {
+        self.workspace.work_dir().join(workspace::MOD_LIST_FILE)
     }
 }
 
+/// First free uid for a local mod. Its archive gets the uid after it.
+fn next_local_uid(mod_list: &ModList) -> u64 {
+    mod_list
+        .mods
+        .iter()
+        .flat_map(|m| std::iter::once(m.uid).chain(m.archives.iter().map(|a| a.file_uid)))
+        .filter(|uid| *uid >= LOCAL_UID_START)
+        .max()
+        .map_or(LOCAL_UID_START, |uid| uid + 1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mods::fomod::{Chooser, Group, InstallStep, PluginType};
     use crate::{
         config::{Config, GameConfig},
         games::Game,
     };
-    use std::{fs::File, os::unix::fs::PermissionsExt};
+    use std::{fs::File, io::Write, os::unix::fs::PermissionsExt};
     use tempfile::TempDir;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    struct FirstOptionChooser;
+
+    impl Chooser for FirstOptionChooser {
+        fn choose(
+            &mut self,
+            _step: &InstallStep,
+            _group: &Group,
+            _types: &[PluginType],
+        ) -> anyhow::Result<Vec<usize>> {
+            Ok(vec![0])
+        }
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &str)]) -> anyhow::Result<()> {
+        let file = File::create(path)?;
+        let mut zip = ZipWriter::new(file);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+        for (name, content) in files {
+            zip.start_file(*name, options)?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.finish()?;
+
+        Ok(())
+    }
 
     fn setup(game: &Game) -> anyhow::Result<Workspace> {
         let tmp_dir = TempDir::new()?;
@@ -203,6 +901,83 @@ mod tests {
         Workspace::new(game, &config)
     }
 
+    #[test]
+    fn add_local_mod_should_move_files_and_register_mod_on_top() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let source_dir = ws.cache_dir().join("source");
+        fs::create_dir_all(source_dir.join("Data"))?;
+        fs::write(source_dir.join("Data/output.nif"), "")?;
+        fs::create_dir_all(ws.mods_dir().join("1"))?;
+
+        // Act
+        let first = sut.add_local_mod("Tool output", "sink", &source_dir)?;
+        let second = sut.add_local_mod("Other output", "sink", &source_dir)?;
+
+        // Assert
+        assert!(first.path.join("Data/output.nif").exists());
+        assert!(fs::read_dir(&source_dir)?.next().is_none());
+        assert_ne!(first.dir_name, second.dir_name);
+
+        let mod_list = sut.read()?;
+        assert_eq!(mod_list.mods.len(), 2);
+        assert_eq!(mod_list.mods[0].archives[0].status, FileStatus::Installed);
+
+        let layers = sut.layers()?;
+        assert_eq!(layers[0].label, "Tool output (sink)");
+        assert_eq!(layers[1].dir_name, second.dir_name);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_local_archive_should_copy_archive_into_staging_and_allow_install() -> anyhow::Result<()>
+    {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let archive_path = ws.cache_dir().join("Hand Made.zip");
+        write_zip(&archive_path, &[("Data/plugin.esp", "plugin")])?;
+
+        // Act
+        let (m, archive) = sut.add_local_archive("Hand Made", &archive_path)?;
+        let (other, _) = sut.add_local_archive("Other", &archive_path)?;
+
+        // Assert
+        assert!(archive_path.exists());
+        assert!(m.uid >= LOCAL_UID_START);
+        assert_ne!(m.uid, other.uid);
+        assert_eq!(m.source(), Source::Local);
+        assert_eq!(
+            archive.archive_path,
+            Some(
+                ws.staging_dir()
+                    .join(m.uid.to_string())
+                    .join(workspace::ARCHIVES)
+                    .join("Hand Made.zip")
+            )
+        );
+        assert_eq!(
+            sut.get_archive_status(m.uid, archive.file_uid)?,
+            FileStatus::Downloaded
+        );
+
+        sut.install_archive(&m, &archive, &mut FirstOptionChooser)?;
+        assert!(
+            ws.mods_dir()
+                .join(archive.file_uid.to_string())
+                .join("Data/plugin.esp")
+                .exists()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn archive_download_dest_should_return_full_archive_path() -> anyhow::Result<()> {
         // Arrange
@@ -232,9 +1007,11 @@ mod tests {
             uid: 1,
             name: "Test mod".to_string(),
             archives: vec![],
+            ..Default::default()
         };
         let expected = ModList {
             mods: vec![m.clone()],
+            priority: vec![],
         };
 
         let mod_list_path = ws.work_dir().join(workspace::MOD_LIST_FILE);
@@ -302,4 +1079,743 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn read_should_return_err_and_back_up_file_when_mod_list_is_corrupt() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let mod_list_path = ws.work_dir().join(workspace::MOD_LIST_FILE);
+        fs::write(&mod_list_path, "{\"mods\": [")?;
+
+        // Act
+        let result = sut.read();
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&mod_list_path)?, "{\"mods\": [");
+
+        let backups: Vec<_> = fs::read_dir(ws.work_dir())?
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().contains("corrupt"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(backups[0].path())?, "{\"mods\": [");
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_should_load_mod_list_written_without_metadata() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        fs::write(
+            ws.work_dir().join(workspace::MOD_LIST_FILE),
+            r#"{"mods": [{"uid": 1, "name": "Test mod", "archives": [
+                {"file_uid": 2, "file_name": "test.7z", "archive_path": null, "status": "downloaded"}
+            ]}]}"#,
+        )?;
+
+        // Act
+        let actual = sut.read()?;
+
+        // Assert
+        assert_eq!(actual.mods[0].mod_id, None);
+        assert_eq!(actual.mods[0].author, None);
+        assert_eq!(actual.mods[0].archives[0].category, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_archive_should_update_metadata_of_existing_mod() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let old = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        sut.add_archive(
+            &old,
+            ModArchive {
+                file_uid: 2,
+                file_name: "test.7z".to_string(),
+                ..Default::default()
+            },
+        )?;
+
+        let fetched = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            mod_id: Some(1),
+            author: Some("Author".to_string()),
+            version: Some("2.0".to_string()),
+            category_id: Some(42),
+            summary: None,
+            source: Some(Source::Nexus),
+            repository: None,
+            asset_pattern: None,
+        };
+
+        // Act
+        sut.add_archive(
+            &fetched,
+            ModArchive {
+                file_uid: 3,
+                file_name: "test-2.0.7z".to_string(),
+                ..Default::default()
+            },
+        )?;
+
+        // Assert
+        let actual = sut.read()?;
+        assert_eq!(actual.mods.len(), 1);
+        assert_eq!(actual.mods[0].archives.len(), 2);
+        assert_eq!(actual.mods[0].author.as_deref(), Some("Author"));
+        assert_eq!(actual.mods[0].version.as_deref(), Some("2.0"));
+        assert_eq!(actual.mods[0].category_id, Some(42));
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_archive_should_keep_all_entries_when_called_concurrently() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws);
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+
+        // Act
+        std::thread::scope(|scope| {
+            for file_uid in 0..8 {
+                let sut = sut.clone();
+                let m = m.clone();
+                scope.spawn(move || {
+                    let archive = ModArchive {
+                        file_uid,
+                        ..Default::default()
+                    };
+                    sut.add_archive(&m, archive).unwrap();
+                });
+            }
+        });
+
+        // Assert
+        let mod_list = sut.read()?;
+        assert_eq!(mod_list.mods.len(), 1);
+        assert_eq!(mod_list.mods[0].archives.len(), 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn claim_download_should_only_claim_once_when_called_concurrently() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws);
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.7z".to_string(),
+            ..Default::default()
+        };
+
+        // Act
+        let previous: Vec<FileStatus> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| sut.claim_download(&m, &archive).unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // Assert
+        let claimed = previous
+            .iter()
+            .filter(|s| **s == FileStatus::Unknown)
+            .count();
+        assert_eq!(claimed, 1);
+        assert_eq!(sut.get_archive_status(1, 2)?, FileStatus::Downloading);
+
+        Ok(())
+    }
+
+    #[test]
+    fn uninstall_archive_should_remove_install_dir_and_mark_archive_as_downloaded()
+    -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.7z".to_string(),
+            archive_path: None,
+            status: FileStatus::Installed,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        let install_dir = ws.mods_dir().join("2");
+        fs::create_dir_all(install_dir.join("textures"))?;
+        fs::write(install_dir.join("textures/file.dds"), "")?;
+
+        // Act
+        let result = sut.uninstall_archive(&m, &archive);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(!install_dir.exists());
+        assert_eq!(sut.get_archive_status(1, 2)?, FileStatus::Downloaded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn uninstall_archive_should_return_err_when_archive_not_in_mod_list() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.7z".to_string(),
+            archive_path: None,
+            status: FileStatus::Installed,
+            ..Default::default()
+        };
+
+        // Act
+        let result = sut.uninstall_archive(&m, &archive);
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_archive_should_drop_mod_when_last_archive_removed() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.7z".to_string(),
+            archive_path: None,
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        let result = sut.remove_archive(&m, &archive, false);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(sut.read()?.mods.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_archive_should_keep_other_archives_of_mod() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let first = ModArchive {
+            file_uid: 2,
+            file_name: "first.7z".to_string(),
+            archive_path: None,
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        let second = ModArchive {
+            file_uid: 3,
+            file_name: "second.7z".to_string(),
+            archive_path: None,
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, first.clone())?;
+        sut.add_archive(&m, second.clone())?;
+
+        // Act
+        let result = sut.remove_archive(&m, &first, false);
+
+        // Assert
+        assert!(result.is_ok());
+
+        let actual = sut.read()?;
+        let actual_mod = actual.mods.first().unwrap();
+        assert_eq!(actual_mod.archives.len(), 1);
+        assert_eq!(
+            actual_mod.archives.first().unwrap().file_uid,
+            second.file_uid
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_archive_should_delete_staged_archive_when_requested() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+
+        let staged_dir = ws.staging_dir().join("1").join(workspace::ARCHIVES);
+        fs::create_dir_all(&staged_dir)?;
+        let staged_archive = staged_dir.join("test.7z");
+        fs::write(&staged_archive, "")?;
+
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.7z".to_string(),
+            archive_path: Some(staged_archive.clone()),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        let result = sut.remove_archive(&m, &archive, true);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(!staged_archive.exists());
+        assert!(!ws.staging_dir().join("1").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_archive_should_extract_archive_into_mods_dir() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(&archive_path, &[("Data/plugin.esp", "plugin")])?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        let result = sut.install_archive(&m, &archive, &mut FirstOptionChooser);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(ws.mods_dir().join("2/Data/plugin.esp").exists());
+        assert!(!ws.extract_dir().join("2").exists());
+        assert_eq!(sut.get_archive_status(1, 2)?, FileStatus::Installed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_archive_should_record_status_change_in_journal() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(&archive_path, &[("Data/plugin.esp", "plugin")])?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        sut.install_archive(&m, &archive, &mut FirstOptionChooser)?;
+
+        // Assert
+        let history = sut.history()?;
+        let operations: Vec<_> = history.iter().map(|e| e.operation).collect();
+        assert_eq!(operations, vec![Operation::Add, Operation::Install]);
+        assert_eq!(history[1].old_status, FileStatus::Downloaded);
+        assert_eq!(history[1].new_status, FileStatus::Installed);
+        assert_eq!((history[1].mod_uid, history[1].file_uid), (1, 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_should_reinstall_from_staged_archive_after_uninstall() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(&archive_path, &[("Data/plugin.esp", "plugin")])?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+        sut.install_archive(&m, &archive, &mut FirstOptionChooser)?;
+        sut.uninstall_archive(&m, &archive)?;
+
+        // Act
+        let reverted = sut.undo(&mut FirstOptionChooser)?;
+
+        // Assert
+        assert_eq!(reverted.map(|e| e.operation), Some(Operation::Uninstall));
+        assert!(ws.mods_dir().join("2/Data/plugin.esp").exists());
+        assert_eq!(sut.get_archive_status(1, 2)?, FileStatus::Installed);
+
+        let reverted = sut.undo(&mut FirstOptionChooser)?;
+        assert_eq!(reverted.map(|e| e.operation), Some(Operation::Install));
+        assert!(!ws.mods_dir().join("2").exists());
+        assert_eq!(sut.get_archive_status(1, 2)?, FileStatus::Downloaded);
+
+        assert!(sut.undo(&mut FirstOptionChooser)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_archive_should_move_wrapped_content_into_data_dir() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(
+            &archive_path,
+            &[
+                ("Wrapper/textures/a.dds", "texture"),
+                ("Wrapper/plugin.esp", "plugin"),
+            ],
+        )?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        let result = sut.install_archive(&m, &archive, &mut FirstOptionChooser);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(ws.mods_dir().join("2/Data/plugin.esp").exists());
+        assert!(ws.mods_dir().join("2/Data/textures/a.dds").exists());
+        assert!(!ws.mods_dir().join("2/Wrapper").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_archive_should_fold_dir_names_to_canonical_casing() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(
+            &archive_path,
+            &[
+                ("data/Textures/a.dds", "texture"),
+                ("data/TEXTURES/b.dds", "texture"),
+            ],
+        )?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        let normalized = sut.install_archive(&m, &archive, &mut FirstOptionChooser)?;
+
+        // Assert
+        assert_eq!(normalized.renamed.len(), 3);
+        assert!(ws.mods_dir().join("2/Data/textures/a.dds").exists());
+        assert!(ws.mods_dir().join("2/Data/textures/b.dds").exists());
+        assert_eq!(fs::read_dir(ws.mods_dir().join("2/Data"))?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_archive_should_only_install_selected_fomod_files() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        let module_config = r#"<config>
+            <moduleName>Test</moduleName>
+            <installSteps><installStep name="Step"><optionalFileGroups>
+                <group name="Group" type="SelectExactlyOne"><plugins order="Explicit">
+                    <plugin name="A">
+                        <files><folder source="A" destination="" /></files>
+                        <typeDescriptor><type name="Optional" /></typeDescriptor>
+                    </plugin>
+                    <plugin name="B">
+                        <files><folder source="B" destination="" /></files>
+                        <typeDescriptor><type name="Optional" /></typeDescriptor>
+                    </plugin>
+                </plugins></group>
+            </optionalFileGroups></installStep></installSteps>
+        </config>"#;
+
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(
+            &archive_path,
+            &[
+                ("fomod/ModuleConfig.xml", module_config),
+                ("A/a.esp", "a"),
+                ("B/b.esp", "b"),
+            ],
+        )?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+
+        // Act
+        let result = sut.install_archive(&m, &archive, &mut FirstOptionChooser);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(ws.mods_dir().join("2/Data/a.esp").exists());
+        assert!(!ws.mods_dir().join("2/Data/b.esp").exists());
+        assert!(!ws.mods_dir().join("2/fomod").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_archive_should_give_installed_archive_highest_priority() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        fs::create_dir_all(ws.mods_dir().join("skse"))?;
+        let archive_path = ws.cache_dir().join("test.zip");
+        write_zip(&archive_path, &[("Data/plugin.esp", "plugin")])?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        let archive = ModArchive {
+            file_uid: 2,
+            file_name: "test.zip".to_string(),
+            archive_path: Some(archive_path),
+            status: FileStatus::Downloaded,
+            ..Default::default()
+        };
+        sut.add_archive(&m, archive.clone())?;
+        sut.set_priority(&sut.layers()?)?;
+
+        // Act
+        sut.install_archive(&m, &archive, &mut FirstOptionChooser)?;
+
+        // Assert
+        assert_eq!(sut.read()?.priority, vec!["skse", "2"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn layers_should_follow_priority_and_put_unknown_folders_on_top() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        for dir in ["10", "20", "b", "a"] {
+            fs::create_dir_all(ws.mods_dir().join(dir))?;
+        }
+        fs::write(ws.mods_dir().join("file.txt"), "")?;
+
+        let m = Mod {
+            uid: 1,
+            name: "Test mod".to_string(),
+            archives: vec![],
+            ..Default::default()
+        };
+        sut.add_archive(
+            &m,
+            ModArchive {
+                file_uid: 20,
+                file_name: "test.7z".to_string(),
+                archive_path: None,
+                status: FileStatus::Installed,
+                ..Default::default()
+            },
+        )?;
+
+        let mut mod_list = sut.read()?;
+        mod_list.priority = vec!["20".to_string(), "removed".to_string(), "10".to_string()];
+        sut.write(&mod_list)?;
+
+        // Act
+        let actual = sut.layers()?;
+
+        // Assert
+        let dir_names: Vec<_> = actual.iter().map(|l| l.dir_name.as_str()).collect();
+        assert_eq!(dir_names, vec!["20", "10", "a", "b"]);
+        assert_eq!(actual[0].label, "Test mod (test.7z)");
+        assert_eq!(actual[1].label, "10");
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_priority_should_store_layer_order() -> anyhow::Result<()> {
+        // Arrange
+        let ws = setup(&Game::SkyrimSE)?;
+        ws.prepare_file_system()?;
+        let sut = ModListStore::new(ws.clone());
+
+        for dir in ["a", "b"] {
+            fs::create_dir_all(ws.mods_dir().join(dir))?;
+        }
+        let mut layers = sut.layers()?;
+        layers.reverse();
+
+        // Act
+        sut.set_priority(&layers)?;
+
+        // Assert
+        let dir_names: Vec<_> = sut.layers()?.into_iter().map(|l| l.dir_name).collect();
+        assert_eq!(dir_names, vec!["b", "a"]);
+
+        Ok(())
+    }
 }
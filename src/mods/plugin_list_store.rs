@@ -0,0 +1,516 @@
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, bail};
+
+use crate::{
+    games::workspace::{self, Workspace},
+    types::{Plugin, PluginList},
+};
+
+const PLUGINS_FILE: &str = "plugins.txt";
+const LOAD_ORDER_FILE: &str = "loadorder.txt";
+
+/// Gap between the modification times of consecutive plugins.
+const PLUGIN_TIME_STEP: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct PluginListStore {
+    workspace: Workspace,
+}
+
+impl PluginListStore {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+
+    pub fn read(&self) -> anyhow::Result<PluginList> {
+        let plugin_list_path = self.workspace.work_dir().join(workspace::PLUGIN_LIST_FILE);
+
+        let plugin_list = match fs::read_to_string(&plugin_list_path) {
+            Ok(content) => serde_json::from_str(&content).with_context(|| {
+                format!("Invalid plugin list at '{}'", plugin_list_path.display())
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PluginList::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(plugin_list)
+    }
+
+    pub fn write(&self, plugin_list: &PluginList) -> anyhow::Result<()> {
+        let plugin_list_path = self.workspace.work_dir().join(workspace::PLUGIN_LIST_FILE);
+
+        let plugin_list_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&plugin_list_path)
+            .with_context(|| "Failed to open plugin list file for writing")?;
+
+        serde_json::to_writer_pretty(plugin_list_file, plugin_list)?;
+
+        Ok(())
+    }
+
+    /// Brings the stored plugin list in line with the installed mods. New plugins are appended
+    /// and enabled, plugins that are no longer installed are dropped.
+    pub fn sync(&self) -> anyhow::Result<PluginList> {
+        let installed = self.discover()?;
+        let mut plugin_list = self.read()?;
+
+        let installed_lower: HashSet<String> = installed.iter().map(|p| p.to_lowercase()).collect();
+        plugin_list
+            .plugins
+            .retain(|p| installed_lower.contains(&p.name.to_lowercase()));
+
+        let known: HashSet<String> = plugin_list
+            .plugins
+            .iter()
+            .map(|p| p.name.to_lowercase())
+            .collect();
+
+        for name in installed {
+            if !known.contains(&name.to_lowercase()) {
+                plugin_list.plugins.push(Plugin {
+                    name,
+                    enabled: true,
+                });
+            }
+        }
+
+        self.write(&plugin_list)?;
+
+        Ok(plugin_list)
+    }
+
+    pub fn set_enabled(&self, names: &[String], enabled: bool) -> anyhow::Result<()> {
+        let mut plugin_list = self.read()?;
+
+        for name in names {
+            match plugin_list
+                .plugins
+                .iter_mut()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+            {
+                Some(plugin) => plugin.enabled = enabled,
+                None => bail!("Plugin '{}' is not installed", name),
+            }
+        }
+
+        self.write(&plugin_list)
+    }
+
+    /// Writes `plugins.txt` and `loadorder.txt` into the game's folder in the Proton prefix. Games
+    /// that don't list disabled plugins sort them by file time instead, so the plugin files get
+    /// ascending modification times too.
+    pub fn write_load_order(&self, plugin_list: &PluginList) -> anyhow::Result<()> {
+        let app_data_dir = self.workspace.app_data_dir();
+        fs::create_dir_all(&app_data_dir)
+            .with_context(|| format!("Failed to create '{}'", app_data_dir.display()))?;
+
+        let ordered = load_order(plugin_list);
+
+        let lists_disabled = self.workspace.game().lists_disabled_plugins();
+        let plugins = ordered
+            .iter()
+            .filter(|p| lists_disabled || p.enabled)
+            .map(|p| match (lists_disabled, p.enabled) {
+                (true, true) => format!("*{}", p.name),
+                _ => p.name.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let load_order = ordered
+            .iter()
+            .map(|p| p.name.to_string())
+            .collect::<Vec<_>>();
+
+        fs::write(
+            app_data_dir.join(PLUGINS_FILE),
+            format!(
+                "# This file is generated by moma.\n{}\n",
+                plugins.join("\n")
+            ),
+        )?;
+        fs::write(
+            app_data_dir.join(LOAD_ORDER_FILE),
+            format!(
+                "# This file is generated by moma.\n{}\n",
+                load_order.join("\n")
+            ),
+        )?;
+
+        if !lists_disabled {
+            self.apply_file_times(&ordered)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the modification times of the plugin files in the mod folders in load order. The
+    /// plugins of the game itself keep their older times and load first.
+    fn apply_file_times(&self, ordered: &[&Plugin]) -> anyhow::Result<()> {
+        let files = self.plugin_files()?;
+        let start = SystemTime::now() - PLUGIN_TIME_STEP * ordered.len() as u32;
+
+        for (i, plugin) in ordered.iter().enumerate() {
+            let modified = start + PLUGIN_TIME_STEP * i as u32;
+
+            let paths = files.iter().filter(|path| {
+                path.file_name()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&plugin.name))
+            });
+            for path in paths {
+                OpenOptions::new()
+                    .write(true)
+                    .open(path)
+                    .and_then(|file| file.set_modified(modified))
+                    .with_context(|| {
+                        format!("Failed to set the file time of '{}'", path.display())
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds all plugin files in the data directories of the installed mods.
+    fn discover(&self) -> anyhow::Result<Vec<String>> {
+        let mut seen = HashSet::new();
+        let mut plugins: Vec<String> = self
+            .plugin_files()?
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .filter(|name| seen.insert(name.to_lowercase()))
+            .collect();
+
+        plugins.sort_by_key(|p| p.to_lowercase());
+
+        Ok(plugins)
+    }
+
+    /// Paths of the plugin files in every mod folder, a plugin shipped by several mods is listed
+    /// once per mod.
+    fn plugin_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let game = self.workspace.game();
+        let mods_dir = self.workspace.mods_dir();
+
+        if !mods_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut files = Vec::new();
+
+        for mod_dir in fs::read_dir(&mods_dir)? {
+            let data_dir = mod_dir?.path().join(game.data_dir());
+            if !data_dir.is_dir() {
+                continue;
+            }
+
+            for entry in fs::read_dir(&data_dir)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                let is_plugin = game
+                    .plugin_extensions()
+                    .iter()
+                    .any(|ext| has_extension(&name, ext));
+
+                if entry.file_type()?.is_file() && is_plugin {
+                    files.push(entry.path());
+                }
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+/// The order the game loads the plugins in. The game refuses to load masters after regular
+/// plugins, so they're always moved up front.
+pub fn load_order(plugin_list: &PluginList) -> Vec<&Plugin> {
+    let (masters, regular): (Vec<&Plugin>, Vec<&Plugin>) = plugin_list
+        .plugins
+        .iter()
+        .partition(|p| !has_extension(&p.name, "esp"));
+    masters.into_iter().chain(regular).collect()
+}
+
+fn has_extension(name: &str, ext: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config, GameConfig},
+        games::Game,
+    };
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn setup() -> anyhow::Result<Workspace> {
+        setup_for(&Game::SkyrimSE)
+    }
+
+    fn setup_for(game: &Game) -> anyhow::Result<Workspace> {
+        let tmp_dir = TempDir::new()?;
+
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = Config::test_with_config(tmp_dir.path().to_owned(), game_config);
+        let workspace = Workspace::new(game, &config)?;
+        workspace.prepare_file_system()?;
+
+        Ok(workspace)
+    }
+
+    fn install_plugin(workspace: &Workspace, mod_dir: &str, name: &str) -> anyhow::Result<()> {
+        let data_dir = workspace.mods_dir().join(mod_dir).join("Data");
+        fs::create_dir_all(&data_dir)?;
+        fs::write(data_dir.join(name), "")?;
+
+        Ok(())
+    }
+
+    fn names(plugin_list: &PluginList) -> Vec<&str> {
+        plugin_list
+            .plugins
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn read_should_return_default_if_no_file_found() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup()?;
+        let sut = PluginListStore::new(workspace);
+
+        // Act
+        let actual = sut.read()?;
+
+        // Assert
+        assert!(actual.plugins.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sync_should_add_new_plugins_as_enabled() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup()?;
+        install_plugin(&workspace, "1", "b.esp")?;
+        install_plugin(&workspace, "2", "a.esm")?;
+        fs::write(workspace.mods_dir().join("1/Data/texture.dds"), "")?;
+
+        let sut = PluginListStore::new(workspace);
+
+        // Act
+        let actual = sut.sync()?;
+
+        // Assert
+        assert_eq!(names(&actual), vec!["a.esm", "b.esp"]);
+        assert!(actual.plugins.iter().all(|p| p.enabled));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sync_should_keep_existing_order_and_drop_removed_plugins() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup()?;
+        install_plugin(&workspace, "1", "a.esp")?;
+        install_plugin(&workspace, "1", "b.esp")?;
+        install_plugin(&workspace, "1", "new.esp")?;
+
+        let sut = PluginListStore::new(workspace);
+        sut.write(&PluginList {
+            plugins: vec![
+                Plugin {
+                    name: "b.esp".to_string(),
+                    enabled: false,
+                },
+                Plugin {
+                    name: "removed.esp".to_string(),
+                    enabled: true,
+                },
+                Plugin {
+                    name: "a.esp".to_string(),
+                    enabled: true,
+                },
+            ],
+        })?;
+
+        // Act
+        let actual = sut.sync()?;
+
+        // Assert
+        assert_eq!(names(&actual), vec!["b.esp", "a.esp", "new.esp"]);
+        assert!(!actual.plugins[0].enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_enabled_should_return_err_when_plugin_unknown() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup()?;
+        let sut = PluginListStore::new(workspace);
+
+        // Act
+        let result = sut.set_enabled(&["missing.esp".to_string()], true);
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_enabled_should_update_plugin_ignoring_case() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup()?;
+        install_plugin(&workspace, "1", "Plugin.esp")?;
+        let sut = PluginListStore::new(workspace);
+        sut.sync()?;
+
+        // Act
+        sut.set_enabled(&["plugin.esp".to_string()], false)?;
+
+        // Assert
+        assert!(!sut.read()?.plugins[0].enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_load_order_should_write_plugin_files_with_masters_first() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup()?;
+        let sut = PluginListStore::new(workspace.clone());
+
+        let plugin_list = PluginList {
+            plugins: vec![
+                Plugin {
+                    name: "a.esp".to_string(),
+                    enabled: true,
+                },
+                Plugin {
+                    name: "b.esp".to_string(),
+                    enabled: false,
+                },
+                Plugin {
+                    name: "c.esm".to_string(),
+                    enabled: true,
+                },
+            ],
+        };
+
+        // Act
+        sut.write_load_order(&plugin_list)?;
+
+        // Assert
+        let plugins = fs::read_to_string(workspace.app_data_dir().join(PLUGINS_FILE))?;
+        let load_order = fs::read_to_string(workspace.app_data_dir().join(LOAD_ORDER_FILE))?;
+
+        let plugin_lines: Vec<_> = plugins.lines().skip(1).collect();
+        let load_order_lines: Vec<_> = load_order.lines().skip(1).collect();
+
+        assert_eq!(plugin_lines, vec!["*c.esm", "*a.esp", "b.esp"]);
+        assert_eq!(load_order_lines, vec!["c.esm", "a.esp", "b.esp"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_load_order_should_only_list_enabled_plugins_for_older_games() -> anyhow::Result<()> {
+        for game in [Game::SkyrimLE, Game::FalloutNV] {
+            // Arrange
+            let workspace = setup_for(&game)?;
+            let sut = PluginListStore::new(workspace.clone());
+
+            let plugin_list = PluginList {
+                plugins: vec![
+                    Plugin {
+                        name: "a.esp".to_string(),
+                        enabled: true,
+                    },
+                    Plugin {
+                        name: "b.esp".to_string(),
+                        enabled: false,
+                    },
+                    Plugin {
+                        name: "c.esm".to_string(),
+                        enabled: true,
+                    },
+                ],
+            };
+
+            // Act
+            sut.write_load_order(&plugin_list)?;
+
+            // Assert
+            let plugins = fs::read_to_string(workspace.app_data_dir().join(PLUGINS_FILE))?;
+            let plugin_lines: Vec<_> = plugins.lines().skip(1).collect();
+
+            assert_eq!(plugin_lines, vec!["c.esm", "a.esp"], "{}", game);
+            assert!(
+                workspace
+                    .app_data_dir()
+                    .ends_with(format!("AppData/Local/{}", game.app_data_dir()))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_load_order_should_set_ascending_file_times_for_older_games() -> anyhow::Result<()> {
+        // Arrange
+        let workspace = setup_for(&Game::FalloutNV)?;
+        install_plugin(&workspace, "1", "a.esp")?;
+        install_plugin(&workspace, "2", "b.esp")?;
+        install_plugin(&workspace, "3", "b.esp")?;
+        install_plugin(&workspace, "3", "c.esm")?;
+        let sut = PluginListStore::new(workspace.clone());
+
+        let plugin_list = PluginList {
+            plugins: ["b.esp", "a.esp", "c.esm"]
+                .into_iter()
+                .map(|name| Plugin {
+                    name: name.to_string(),
+                    enabled: true,
+                })
+                .collect(),
+        };
+
+        // Act
+        sut.write_load_order(&plugin_list)?;
+
+        // Assert
+        let modified = |path: &str| -> anyhow::Result<SystemTime> {
+            Ok(fs::metadata(workspace.mods_dir().join(path))?.modified()?)
+        };
+        assert!(modified("3/Data/c.esm")? < modified("2/Data/b.esp")?);
+        assert_eq!(modified("2/Data/b.esp")?, modified("3/Data/b.esp")?);
+        assert!(modified("2/Data/b.esp")? < modified("1/Data/a.esp")?);
+
+        Ok(())
+    }
+}
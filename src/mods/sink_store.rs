@@ -0,0 +1,208 @@
+use std::{
+    fs,
+    os::unix::fs::FileTypeExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use walkdir::WalkDir;
+
+use crate::{games::workspace::Workspace, mods::mod_list_store::ModListStore, types::ModLayer};
+
+/// A file the game or one of its tools left behind in the sink.
+pub struct SinkEntry {
+    /// Path relative to the sink directory.
+    pub path: PathBuf,
+    pub size: u64,
+    /// Overlayfs marks files deleted from lower layers with a whiteout instead of a regular file.
+    pub is_whiteout: bool,
+}
+
+#[derive(Clone)]
+pub struct SinkStore {
+    workspace: Workspace,
+}
+
+impl SinkStore {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+
+    pub fn entries(&self) -> anyhow::Result<Vec<SinkEntry>> {
+        let sink_dir = self.workspace.sink_dir();
+        if !sink_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut entries = Vec::new();
+        for entry in WalkDir::new(&sink_dir).min_depth(1).sort_by_file_name() {
+            let entry = entry?;
+            if entry.file_type().is_dir() {
+                continue;
+            }
+
+            entries.push(SinkEntry {
+                path: entry.path().strip_prefix(&sink_dir)?.to_path_buf(),
+                size: entry.metadata()?.len(),
+                is_whiteout: entry.file_type().is_char_device(),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Removes everything in the sink, leaving the directory itself in place for the next mount.
+    pub fn clear(&self) -> anyhow::Result<usize> {
+        let sink_dir = self.workspace.sink_dir();
+        if !sink_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in fs::read_dir(&sink_dir)? {
+            let path = entry?.path();
+            remove_path(&path)?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Turns the current sink contents into a new mod, so they can be ordered like any other mod.
+    pub fn promote(&self, name: &str) -> anyhow::Result<ModLayer> {
+        let sink_dir = self.workspace.sink_dir();
+        if !sink_dir.exists() || fs::read_dir(&sink_dir)?.next().is_none() {
+            bail!("Sink is empty, there is nothing to promote.");
+        }
+
+        let mod_list_store = ModListStore::new(self.workspace.clone());
+        mod_list_store.add_local_mod(name, "sink", &sink_dir)
+    }
+}
+
+fn remove_path(path: &Path) -> anyhow::Result<()> {
+    if path.is_dir() && !path.is_symlink() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config, GameConfig},
+        games::Game,
+    };
+    use tempfile::TempDir;
+
+    fn setup() -> anyhow::Result<(TempDir, Workspace)> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = Config::test_with_config(tmp_dir.path().to_owned(), game_config);
+        let workspace = Workspace::new(&game, &config)?;
+        workspace.prepare_file_system()?;
+
+        Ok((tmp_dir, workspace))
+    }
+
+    fn write_sink_file(workspace: &Workspace, path: &str) -> anyhow::Result<()> {
+        let path = workspace.sink_dir().join(path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, "content")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn entries_should_list_files_relative_to_sink() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        write_sink_file(&workspace, "Data/SKSE/skse64.log")?;
+        write_sink_file(&workspace, "Data/meshes/body.nif")?;
+        let sut = SinkStore::new(workspace);
+
+        // Act
+        let actual = sut.entries()?;
+
+        // Assert
+        let paths: Vec<_> = actual.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("Data/SKSE/skse64.log"),
+                PathBuf::from("Data/meshes/body.nif")
+            ]
+        );
+        assert!(actual.iter().all(|e| e.size == 7 && !e.is_whiteout));
+
+        Ok(())
+    }
+
+    #[test]
+    fn clear_should_empty_sink_but_keep_directory() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        write_sink_file(&workspace, "Data/SKSE/skse64.log")?;
+        write_sink_file(&workspace, "SkyrimPrefs.ini")?;
+        let sut = SinkStore::new(workspace.clone());
+
+        // Act
+        let removed = sut.clear()?;
+
+        // Assert
+        assert_eq!(removed, 2);
+        assert!(workspace.sink_dir().exists());
+        assert!(fs::read_dir(workspace.sink_dir())?.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn promote_should_move_sink_into_new_mod() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        write_sink_file(&workspace, "Data/meshes/body.nif")?;
+        let sut = SinkStore::new(workspace.clone());
+
+        // Act
+        let layer = sut.promote("BodySlide output")?;
+
+        // Assert
+        assert!(layer.path.join("Data/meshes/body.nif").exists());
+        assert!(fs::read_dir(workspace.sink_dir())?.next().is_none());
+
+        let mod_list = ModListStore::new(workspace).read()?;
+        assert_eq!(mod_list.mods[0].name, "BodySlide output");
+        assert_eq!(mod_list.priority, vec![layer.dir_name]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn promote_should_return_err_when_sink_empty() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        let sut = SinkStore::new(workspace);
+
+        // Act
+        let result = sut.promote("Nothing");
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
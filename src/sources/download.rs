@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use futures::TryStreamExt;
+use reqwest::{Client, StatusCode, Url, header};
+use tokio_util::io::StreamReader;
+
+use crate::mods::download_tracker::{Checksum, DownloadTracker};
+
+/// Downloads the file with tracking, continuing a previous partial download when the server
+/// supports range requests. Servers that ignore the range get the full file downloaded again.
+/// Returns the checksum of the downloaded file.
+pub async fn download_file(
+    client: &Client,
+    download_tracker: &DownloadTracker,
+    url: &Url,
+    output_file: &Path,
+    tracking_file: &Path,
+) -> anyhow::Result<Checksum> {
+    let mut offset = download_tracker.resume_offset(output_file, tracking_file)?;
+
+    let mut request = client.get(url.clone());
+    if offset > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", offset));
+    }
+
+    let mut res = request.send().await?;
+
+    if offset > 0 && res.status() != StatusCode::PARTIAL_CONTENT {
+        log::warn!(
+            "Server did not resume download at byte {} ({}), restarting.",
+            offset,
+            res.status()
+        );
+        offset = 0;
+
+        // The range is still in the request, a server that rejects it has sent no content.
+        if !res.status().is_success() {
+            res = client.get(url.clone()).send().await?;
+        }
+    }
+
+    let res = res.error_for_status()?;
+    let total_size = res.content_length().map_or(0, |length| offset + length);
+
+    let stream = StreamReader::new(res.bytes_stream().map_err(std::io::Error::other));
+
+    download_tracker
+        .stream_to_file_with_tracking(
+            stream,
+            output_file,
+            offset,
+            total_size,
+            tracking_file,
+            output_file.file_name().unwrap().display().to_string(),
+        )
+        .await
+}
@@ -0,0 +1,628 @@
+use std::fmt::{self, Display};
+
+use anyhow::{Context, bail};
+use reqwest::{
+    Client, StatusCode, Url,
+    header::{self, HeaderMap, HeaderValue},
+};
+use serde::Deserialize;
+
+use crate::{
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::{
+        Source,
+        nexus::{self, ArchiveUpdate, NewerFile},
+        url::UrlDownloader,
+    },
+    types::{FileStatus, Mod, ModArchive},
+    utils::fs::is_supported_archive,
+};
+
+pub const DEFAULT_GITHUB_BASE_URL: &str = "https://api.github.com/";
+
+/// Prefix marking a mod source argument as a GitHub repository, e.g. `github:owner/repo`.
+pub const SPEC_PREFIX: &str = "github:";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Repository {
+    pub owner: String,
+    pub name: String,
+}
+
+impl Display for Repository {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+/// Parses `owner/repo`, with or without the `github:` prefix.
+pub fn parse_repository(spec: &str) -> anyhow::Result<Repository> {
+    let spec = spec.strip_prefix(SPEC_PREFIX).unwrap_or(spec);
+
+    match spec.trim_end_matches('/').split('/').collect::<Vec<_>>()[..] {
+        [owner, name] if !owner.is_empty() && !name.is_empty() => Ok(Repository {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        }),
+        _ => bail!(
+            "Invalid GitHub repository '{}', expected '{}owner/repo'",
+            spec,
+            SPEC_PREFIX
+        ),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    pub size: u64,
+}
+
+/// Picks the archive to download from a release. Without a pattern the release has to contain
+/// exactly one archive.
+pub fn pick_asset<'a>(
+    release: &'a Release,
+    pattern: Option<&str>,
+) -> anyhow::Result<&'a ReleaseAsset> {
+    let archives: Vec<&ReleaseAsset> = release
+        .assets
+        .iter()
+        .filter(|a| is_supported_archive(a.name.as_ref()))
+        .collect();
+    let names = || {
+        archives
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match pattern {
+        Some(pattern) => match archives.iter().find(|a| matches_glob(pattern, &a.name)) {
+            Some(asset) => Ok(asset),
+            None => bail!(
+                "No archive of release {} matches '{}', available: {}",
+                release.tag_name,
+                pattern,
+                names()
+            ),
+        },
+        None => match archives[..] {
+            [asset] => Ok(asset),
+            [] => bail!("Release {} has no archive assets", release.tag_name),
+            _ => bail!(
+                "Release {} has several archives ({}), pick one with --asset-pattern",
+                release.tag_name,
+                names()
+            ),
+        },
+    }
+}
+
+/// Matches `name` against a glob with `*` and `?` wildcards, ignoring case.
+pub fn matches_glob(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it is currently standing in for.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Mods published as GitHub releases, downloaded through the same tracking as url downloads.
+pub struct GitHub {
+    client: Client,
+    base_url: Url,
+    downloader: UrlDownloader,
+}
+
+// Documentation: https://docs.github.com/en/rest/releases/releases
+impl GitHub {
+    pub fn new(download_tracker: DownloadTracker) -> anyhow::Result<Self> {
+        Self::with_base_url(download_tracker, DEFAULT_GITHUB_BASE_URL)
+    }
+
+    pub fn with_base_url(
+        download_tracker: DownloadTracker,
+        base_url: &str,
+    ) -> anyhow::Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .user_agent(concat!("moma/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url: Url::parse(base_url)?,
+            downloader: UrlDownloader::new(download_tracker)?,
+        })
+    }
+
+    pub async fn latest_release(&self, repository: &Repository) -> anyhow::Result<Release> {
+        let url = self.base_url.join(&format!(
+            "repos/{}/{}/releases/latest",
+            repository.owner, repository.name
+        ))?;
+
+        let res = self.client.get(url).send().await?;
+        match res.status() {
+            status if status.is_success() => res
+                .json()
+                .await
+                .with_context(|| format!("Failed to read the latest release of {}", repository)),
+            StatusCode::NOT_FOUND => bail!("No published release found for '{}'", repository),
+            status => bail!(
+                "GitHub returned {} for the latest release of '{}'",
+                status,
+                repository
+            ),
+        }
+    }
+
+    /// Downloads the latest release asset of `repository` and stages it, adding it to the mod
+    /// already tracking the repository if there is one. The release tag becomes the version once
+    /// the download succeeded, so a failed one still shows up as an update.
+    pub async fn add_latest_release(
+        &self,
+        mod_list_store: &ModListStore,
+        repository: &Repository,
+        name: Option<&str>,
+        asset_pattern: Option<&str>,
+    ) -> anyhow::Result<(Mod, ModArchive)> {
+        let existing = mod_list_store.read()?.mods.into_iter().find(|m| {
+            m.repository
+                .as_deref()
+                .is_some_and(|r| r.eq_ignore_ascii_case(&repository.to_string()))
+        });
+
+        let asset_pattern = asset_pattern
+            .map(str::to_string)
+            .or_else(|| existing.as_ref().and_then(|m| m.asset_pattern.clone()));
+
+        let release = self.latest_release(repository).await?;
+        let asset = pick_asset(&release, asset_pattern.as_deref())?;
+
+        let known = existing.as_ref().and_then(|m| {
+            m.archives
+                .iter()
+                .find(|a| a.url.as_deref() == Some(asset.browser_download_url.as_str()))
+                .cloned()
+        });
+
+        let mod_template = Mod {
+            uid: existing.as_ref().map_or(0, |m| m.uid),
+            name: name
+                .map(str::to_string)
+                .or_else(|| existing.as_ref().map(|m| m.name.clone()))
+                .unwrap_or_else(|| repository.name.clone()),
+            author: Some(repository.owner.clone()),
+            source: Some(Source::GitHub),
+            repository: Some(repository.to_string()),
+            asset_pattern,
+            ..Default::default()
+        };
+
+        let (m, archive) = match (&existing, known) {
+            (Some(m), Some(archive))
+                if matches!(
+                    archive.status,
+                    FileStatus::Downloaded | FileStatus::Installed
+                ) =>
+            {
+                bail!("'{}' is already at {}", m.name, release.tag_name)
+            }
+            (Some(m), Some(archive)) => (m.clone(), archive),
+            _ => {
+                // Assets often keep their name across releases, the tag keeps the staged
+                // archive of the previous release from being overwritten.
+                let name_taken = existing
+                    .as_ref()
+                    .is_some_and(|m| m.archives.iter().any(|a| a.file_name == asset.name));
+                let file_name = match name_taken {
+                    true => format!("{}-{}", release.tag_name, asset.name),
+                    false => asset.name.clone(),
+                };
+
+                mod_list_store.add_local_source_archive(
+                    &mod_template,
+                    ModArchive {
+                        file_name,
+                        status: FileStatus::Unknown,
+                        size: Some(asset.size),
+                        version: Some(release.tag_name.clone()),
+                        url: Some(asset.browser_download_url.clone()),
+                        ..Default::default()
+                    },
+                )?
+            }
+        };
+
+        let staged = self.download_archive(mod_list_store, &m, archive).await?;
+        let version = staged.version.clone();
+
+        Ok((Mod { version, ..m }, staged))
+    }
+
+    /// Downloads a release asset that is in the mod list already and stages it. The mod moves to
+    /// the asset's release tag once the download succeeded.
+    pub async fn download_archive(
+        &self,
+        mod_list_store: &ModListStore,
+        m: &Mod,
+        archive: ModArchive,
+    ) -> anyhow::Result<ModArchive> {
+        let staged = self
+            .downloader
+            .download_archive(mod_list_store, m, archive)
+            .await?;
+        mod_list_store.update_mod(m.uid, |stored| stored.version = staged.version.clone())?;
+
+        Ok(staged)
+    }
+
+    /// Compares the release tag of each GitHub mod with the latest release of its repository.
+    pub async fn check_updates(&self, mods: &[Mod]) -> anyhow::Result<Vec<ArchiveUpdate>> {
+        let mut found = Vec::new();
+
+        for m in mods.iter().filter(|m| m.source() == Source::GitHub) {
+            let Some(repository) = m.repository.as_deref() else {
+                continue;
+            };
+            // Archives are added in release order, the newest usable one is what the mod is at.
+            let Some(current) = m
+                .archives
+                .iter()
+                .rev()
+                .find(|a| matches!(a.status, FileStatus::Downloaded | FileStatus::Installed))
+            else {
+                continue;
+            };
+
+            let release = self.latest_release(&parse_repository(repository)?).await?;
+            if current.version.as_deref() == Some(release.tag_name.as_str()) {
+                continue;
+            }
+
+            let file_name = match pick_asset(&release, m.asset_pattern.as_deref()) {
+                Ok(asset) => asset.name.clone(),
+                Err(_) => release.tag_name.clone(),
+            };
+
+            found.push(ArchiveUpdate {
+                mod_name: m.name.clone(),
+                file_name: current.file_name.clone(),
+                version: current.version.clone(),
+                newer_file: Some(NewerFile {
+                    file_name,
+                    version: Some(release.tag_name.clone()),
+                    uploaded_at: None,
+                    changelog: release.body.as_deref().and_then(nexus::changelog_snippet),
+                }),
+                outdated_category: None,
+            });
+        }
+
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config, GameConfig},
+        games::{Game, workspace::Workspace},
+    };
+    use mockito::Server;
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    fn setup_workspace() -> anyhow::Result<Workspace> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = Config::test_with_config(tmp_dir.path().to_owned(), game_config);
+
+        Workspace::new(&game, &config)
+    }
+
+    fn release(tag: &str, asset_names: &[&str]) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            body: None,
+            assets: asset_names
+                .iter()
+                .map(|name| ReleaseAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{}", name),
+                    size: 1,
+                })
+                .collect(),
+        }
+    }
+
+    fn release_json(server_url: &str, tag: &str, asset_name: &str, size: usize) -> String {
+        serde_json::json!({
+            "tag_name": tag,
+            "body": "## Changes\n\n* Fixed a crash",
+            "assets": [
+                {
+                    "name": "checksums.txt",
+                    "browser_download_url": format!("{}/download/checksums.txt", server_url),
+                    "size": 10
+                },
+                {
+                    "name": asset_name,
+                    "browser_download_url": format!("{}/download/{}/{}", server_url, tag, asset_name),
+                    "size": size
+                }
+            ]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn parse_repository_should_accept_prefixed_and_plain_specs() -> anyhow::Result<()> {
+        // Arrange
+        let expected = Repository {
+            owner: "powerof3".to_string(),
+            name: "PapyrusExtenderSSE".to_string(),
+        };
+
+        // Act & Assert
+        assert_eq!(
+            parse_repository("github:powerof3/PapyrusExtenderSSE")?,
+            expected
+        );
+        assert_eq!(parse_repository("powerof3/PapyrusExtenderSSE/")?, expected);
+        assert!(parse_repository("github:powerof3").is_err());
+        assert!(parse_repository("github:a/b/c").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn matches_glob_should_support_wildcards_and_ignore_case() -> anyhow::Result<()> {
+        // Act & Assert
+        assert!(matches_glob("*-SE.7z", "EngineFixes-se.7z"));
+        assert!(matches_glob("Plugin-v?.zip", "plugin-v2.zip"));
+        assert!(matches_glob("*", "anything.rar"));
+        assert!(matches_glob("a*b*c", "aXXbYYbZc"));
+        assert!(!matches_glob("*-SE.7z", "EngineFixes-AE.7z"));
+        assert!(!matches_glob("Plugin-v?.zip", "Plugin-v10.zip"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pick_asset_should_require_pattern_when_release_has_several_archives() -> anyhow::Result<()> {
+        // Arrange
+        let sut = release("v1.0", &["Mod-SE.7z", "Mod-AE.7z", "notes.txt"]);
+
+        // Act
+        let without_pattern = pick_asset(&sut, None);
+        let with_pattern = pick_asset(&sut, Some("*-ae.7z"))?;
+
+        // Assert
+        assert!(without_pattern.is_err());
+        assert_eq!(with_pattern.name, "Mod-AE.7z");
+        assert_eq!(
+            pick_asset(&release("v1.0", &["Mod.zip", "notes.txt"]), None)?.name,
+            "Mod.zip"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_latest_release_should_download_asset_and_record_tag() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/Plugin/releases/latest")
+            .with_status(200)
+            .with_body(release_json(&server.url(), "v1.2.0", "Plugin.7z", 11))
+            .create_async()
+            .await;
+        let download = server
+            .mock("GET", "/download/v1.2.0/Plugin.7z")
+            .with_status(200)
+            .with_body("hello world")
+            .create_async()
+            .await;
+
+        let workspace = setup_workspace()?;
+        workspace.prepare_file_system()?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+        let sut = GitHub::with_base_url(download_tracker, &format!("{}/", server.url()))?;
+        let repository = parse_repository("github:owner/Plugin")?;
+
+        // Act
+        let (m, archive) = sut
+            .add_latest_release(&mod_list_store, &repository, None, None)
+            .await?;
+
+        // Assert
+        download.assert_async().await;
+        assert_eq!(m.name, "Plugin");
+        assert_eq!(m.source(), Source::GitHub);
+        assert_eq!(m.version.as_deref(), Some("v1.2.0"));
+        assert_eq!(m.repository.as_deref(), Some("owner/Plugin"));
+        assert_eq!(archive.status, FileStatus::Downloaded);
+        assert_eq!(archive.version.as_deref(), Some("v1.2.0"));
+        assert_eq!(
+            fs::read_to_string(archive.archive_path.unwrap())?,
+            "hello world"
+        );
+        assert!(
+            sut.add_latest_release(&mod_list_store, &repository, None, None)
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_updates_should_report_newer_release_tag() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/Plugin/releases/latest")
+            .with_status(200)
+            .with_body(release_json(&server.url(), "v2.0.0", "Plugin.7z", 11))
+            .create_async()
+            .await;
+
+        let workspace = setup_workspace()?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store);
+        let sut = GitHub::with_base_url(download_tracker, &format!("{}/", server.url()))?;
+
+        let mods = vec![Mod {
+            uid: 1 << 48,
+            name: "Plugin".to_string(),
+            version: Some("v1.2.0".to_string()),
+            source: Some(Source::GitHub),
+            repository: Some("owner/Plugin".to_string()),
+            archives: vec![ModArchive {
+                file_uid: (1 << 48) + 1,
+                file_name: "Plugin.7z".to_string(),
+                status: FileStatus::Installed,
+                version: Some("v1.2.0".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+
+        // Act
+        let actual = sut.check_updates(&mods).await?;
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![ArchiveUpdate {
+                mod_name: "Plugin".to_string(),
+                file_name: "Plugin.7z".to_string(),
+                version: Some("v1.2.0".to_string()),
+                newer_file: Some(NewerFile {
+                    file_name: "Plugin.7z".to_string(),
+                    version: Some("v2.0.0".to_string()),
+                    uploaded_at: None,
+                    changelog: Some("## Changes * Fixed a crash".to_string()),
+                }),
+                outdated_category: None,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_latest_release_should_keep_update_pending_when_download_fails()
+    -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/Plugin/releases/latest")
+            .with_status(200)
+            .with_body(release_json(&server.url(), "v2.0.0", "Plugin.7z", 11))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/download/v2.0.0/Plugin.7z")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let workspace = setup_workspace()?;
+        workspace.prepare_file_system()?;
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace, mod_list_store.clone());
+        let sut = GitHub::with_base_url(download_tracker, &format!("{}/", server.url()))?;
+        let repository = parse_repository("github:owner/Plugin")?;
+
+        mod_list_store.add_local_source_archive(
+            &Mod {
+                name: "Plugin".to_string(),
+                version: Some("v1.2.0".to_string()),
+                source: Some(Source::GitHub),
+                repository: Some("owner/Plugin".to_string()),
+                ..Default::default()
+            },
+            ModArchive {
+                file_name: "Plugin.7z".to_string(),
+                status: FileStatus::Installed,
+                version: Some("v1.2.0".to_string()),
+                ..Default::default()
+            },
+        )?;
+
+        // Act
+        let result = sut
+            .add_latest_release(&mod_list_store, &repository, None, None)
+            .await;
+
+        // Assert
+        assert!(result.is_err());
+
+        let mods = mod_list_store.read()?.mods;
+        assert_eq!(mods[0].version.as_deref(), Some("v1.2.0"));
+        assert!(matches!(mods[0].archives[1].status, FileStatus::Failed(_)));
+
+        let updates = sut.check_updates(&mods).await?;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].version.as_deref(), Some("v1.2.0"));
+
+        Ok(())
+    }
+}
@@ -3,17 +3,29 @@ use std::fmt::{self, Display};
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
+pub mod download;
+pub mod github;
 pub mod nexus;
+pub mod url;
 
-#[derive(clap::ValueEnum, Clone, Serialize, Deserialize, EnumIter)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
 pub enum Source {
     Nexus,
+    /// Archives added from disk, e.g. downloaded by hand from a forum.
+    Local,
+    /// Archives fetched from a direct http(s) link, e.g. script extenders.
+    Url,
+    /// Release assets of a GitHub repository, e.g. SKSE plugins published there.
+    GitHub,
 }
 
 impl Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let name = match self {
             Source::Nexus => "Nexus",
+            Source::Local => "Local",
+            Source::Url => "URL",
+            Source::GitHub => "GitHub",
         };
 
         write!(f, "{}", name)
@@ -1,12 +1,30 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+use anyhow::Context;
 use reqwest::Url;
 
 use crate::{
     games::Game,
-    mods::download_tracker::DownloadTracker,
-    sources::nexus::{client::NexusClient, config::Config, types::DownloadInfoRequest},
-    types::{Mod, ModArchive},
+    mods::{
+        download_tracker::{Checksum, DownloadTracker},
+        mod_list_store::ModListStore,
+    },
+    sources::Source,
+    sources::nexus::{
+        self,
+        client::NexusClient,
+        collection::{self, CollectionLink, CollectionManifest},
+        config::Config,
+        rate_limit::RateLimit,
+        types::{self, DownloadInfoRequest, ValidateResponse},
+        updates::{self, ArchiveUpdate},
+    },
+    types::{FileStatus, Mod, ModArchive},
+    utils::fs::extract_archive,
 };
 
 #[derive(Clone)]
@@ -24,23 +42,33 @@ impl From<NxmLink> for DownloadInfoRequest {
             game: link.game,
             mod_id: link.mod_id,
             file_id: link.file_id,
-            key: link.key,
-            expires: link.expires,
+            key: Some(link.key),
+            expires: Some(link.expires),
         }
     }
 }
 
+/// The longest period `updated.json` accepts.
+const UPDATED_PERIOD_SECS: u64 = 30 * 24 * 60 * 60;
+
 pub struct Nexus {
     client: NexusClient,
+    download_tracker: DownloadTracker,
 }
 
 impl Nexus {
     pub fn new(config: &Config, download_tracker: DownloadTracker) -> anyhow::Result<Self> {
         Ok(Self {
-            client: NexusClient::new(config, download_tracker)?,
+            client: NexusClient::new(config, download_tracker.clone())?,
+            download_tracker,
         })
     }
 
+    /// Checks the configured API key and returns the account with its current rate limit.
+    pub async fn status(config: &Config) -> anyhow::Result<(ValidateResponse, Option<RateLimit>)> {
+        NexusClient::status(config).await
+    }
+
     pub async fn get_mod_info(&self, game: &Game, mod_id: &str) -> anyhow::Result<Mod> {
         let response = self.client.get_mod_info(game, mod_id).await?;
         Ok(response.into())
@@ -62,14 +90,250 @@ impl Nexus {
         Ok(Url::parse(&response.uri)?)
     }
 
-    pub async fn download_file(
+    /// Requests a download link without the key from an nxm link, Nexus only allows this for
+    /// premium accounts.
+    pub async fn get_premium_download_link(
+        &self,
+        game: &Game,
+        mod_uid: u64,
+        file_uid: u64,
+    ) -> anyhow::Result<Url> {
+        let request = DownloadInfoRequest {
+            game: nexus::to_nexus_domain(game)?.to_string(),
+            mod_id: types::id_from_uid(mod_uid).to_string(),
+            file_id: types::id_from_uid(file_uid).to_string(),
+            key: None,
+            expires: None,
+        };
+
+        let response = self.client.get_download_link(request).await?;
+        Ok(Url::parse(&response.uri)?)
+    }
+
+    /// Checks the Nexus mods for newer files. Mods without a file update in the last month are
+    /// skipped when every archive is younger than that, saving one request per mod.
+    pub async fn check_updates(
+        &self,
+        game: &Game,
+        mods: &[Mod],
+    ) -> anyhow::Result<Vec<ArchiveUpdate>> {
+        let updated_mods = self.client.get_updated_mods(game, "1m").await?;
+        let month_ago = SystemTime::now()
+            .duration_since(UNIX_EPOCH)?
+            .as_secs()
+            .saturating_sub(UPDATED_PERIOD_SECS);
+
+        let mut found = Vec::new();
+        for m in mods.iter().filter(|m| m.source() == Source::Nexus) {
+            let mod_id = types::id_from_uid(m.uid);
+            let oldest_upload = m.archives.iter().map(|a| a.uploaded_at).min().flatten();
+            let latest_update = updated_mods
+                .iter()
+                .find(|u| u.mod_id == mod_id)
+                .map(|u| u.latest_file_update);
+
+            let up_to_date = match (latest_update, oldest_upload) {
+                (Some(latest_update), Some(uploaded_at)) => latest_update <= uploaded_at,
+                (None, Some(uploaded_at)) => uploaded_at >= month_ago,
+                _ => false,
+            };
+            if up_to_date {
+                continue;
+            }
+
+            let files = self.client.get_mod_files(game, mod_id).await?;
+            found.extend(updates::find_updates(m, &files));
+        }
+
+        Ok(found)
+    }
+
+    /// Downloads the bundle of a collection revision into `dest_dir` and reads its manifest.
+    /// Bundles already downloaded for the same revision are reused.
+    pub async fn get_collection(
+        &self,
+        game: &Game,
+        link: &CollectionLink,
+        dest_dir: &Path,
+    ) -> anyhow::Result<CollectionManifest> {
+        let domain = match &link.game {
+            Some(domain) => domain.clone(),
+            None => nexus::to_nexus_domain(game)?.to_string(),
+        };
+
+        let revision = self
+            .client
+            .get_collection_revision(&link.slug, Some(&domain), link.revision)
+            .await?;
+        let name = format!("{}-{}", link.slug, revision.revision_number);
+
+        fs::create_dir_all(dest_dir)?;
+        let bundle_path = dest_dir.join(format!("{}.7z", name));
+        if !bundle_path.exists() {
+            let url = self
+                .client
+                .get_collection_download_link(&revision.download_link)
+                .await?;
+            self.client.download_bundle(&url, &bundle_path).await?;
+        }
+
+        let extract_dir = dest_dir.join(&name);
+        if extract_dir.exists() {
+            fs::remove_dir_all(&extract_dir)?;
+        }
+        extract_archive(&bundle_path, &extract_dir, true)?;
+
+        let manifest_path = extract_dir.join(collection::MANIFEST_FILE);
+        let content = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Collection bundle has no '{}'", collection::MANIFEST_FILE))?;
+
+        serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Invalid collection manifest at '{}'",
+                manifest_path.display()
+            )
+        })
+    }
+
+    /// Downloads the archive into the cache with tracking and moves it to the staging directory
+    /// once its checksum is verified. The archive is marked as failed when anything goes wrong.
+    pub async fn download_archive(
+        &self,
+        mod_list_store: &ModListStore,
+        m: &Mod,
+        archive: &ModArchive,
+        url: &Url,
+    ) -> anyhow::Result<()> {
+        let archive_path = mod_list_store.archive_download_dest(&archive.file_name);
+
+        let mut archive = archive.clone();
+        archive.status = FileStatus::Downloading;
+        archive.archive_path = Some(archive_path);
+
+        mod_list_store.add_archive(m, archive.clone())?;
+        mod_list_store.update_archive(&m.uid, &archive.file_uid, |a| *a = archive.clone())?;
+
+        self.download_claimed_archive(mod_list_store, m, &archive, url)
+            .await
+    }
+
+    /// Downloads an archive that [`ModListStore::claim_download`] already marked as downloading.
+    pub async fn download_claimed_archive(
         &self,
+        mod_list_store: &ModListStore,
+        m: &Mod,
+        archive: &ModArchive,
         url: &Url,
-        output_file: &PathBuf,
-        tracking_file: &PathBuf,
     ) -> anyhow::Result<()> {
+        let archive_path = mod_list_store.archive_download_dest(&archive.file_name);
+        let archive = ModArchive {
+            archive_path: Some(archive_path.clone()),
+            ..archive.clone()
+        };
+
+        let download = async {
+            let tracking_file = self
+                .download_tracker
+                .ensure_tracking_file(archive.file_uid)
+                .with_context(|| {
+                    format!(
+                        "Could not resolve tracking file path for {}",
+                        archive.file_name
+                    )
+                })?;
+
+            self.download_file(url, &archive_path, &tracking_file).await
+        };
+        self.download_tracker
+            .checked_download(m.uid, &archive, download)
+            .await?;
+
+        mod_list_store.stage_archive(m, &archive)
+    }
+
+    pub async fn download_file(
+        &self,
+        url: &Url,
+        output_file: &Path,
+        tracking_file: &Path,
+    ) -> anyhow::Result<Checksum> {
         self.client
             .download_file(url, output_file, tracking_file)
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config as MomaConfig, GameConfig};
+    use crate::games::workspace::Workspace;
+    use mockito::Server;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn setup(base_url: &str) -> anyhow::Result<(TempDir, Nexus)> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = MomaConfig::test_with_config(tmp_dir.path().to_owned(), game_config);
+        let workspace = Workspace::new(&game, &config)?;
+        let download_tracker =
+            DownloadTracker::new(workspace.clone(), ModListStore::new(workspace));
+
+        let nexus_config = Config {
+            api_key: Some("key".to_string()),
+            base_url: Some(base_url.to_string()),
+        };
+        let client = NexusClient::test_with_state_file(
+            &nexus_config,
+            download_tracker.clone(),
+            tmp_dir.path().join("rate-limit.json"),
+        )?;
+
+        Ok((
+            tmp_dir,
+            Nexus {
+                client,
+                download_tracker,
+            },
+        ))
+    }
+
+    #[tokio::test]
+    async fn get_premium_download_link_should_request_ids_from_uids() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/games/skyrimspecialedition/mods/12604/files/51380/download_link.json",
+            )
+            .with_body(r#"[{"URI": "https://cdn.example/file.7z"}]"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+        let mod_uid = (1704 << 32) | 12604;
+        let file_uid = (1704 << 32) | 51380;
+
+        // Act
+        let actual = sut
+            .get_premium_download_link(&Game::SkyrimSE, mod_uid, file_uid)
+            .await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(actual.as_str(), "https://cdn.example/file.7z");
+
+        Ok(())
+    }
+}
@@ -1,21 +1,27 @@
 use anyhow::{Context, bail};
-use futures::TryStreamExt;
 use reqwest::{
-    Client, Url,
+    Client, Response, Url,
     header::{self, HeaderMap, HeaderValue},
 };
-use std::path::PathBuf;
-use tokio_util::io::StreamReader;
+use serde::de::DeserializeOwned;
+use std::{fs, path::Path};
 
 use crate::{
     games::Game,
-    mods::download_tracker::DownloadTracker,
-    sources::nexus::{
-        self,
-        config::Config,
-        types::{
-            DownloadInfoRequest, DownloadInfoResponse, ModFileInfoResponse, ModInfoResponse,
-            ValidateResponse,
+    mods::download_tracker::{Checksum, DownloadTracker},
+    sources::{
+        download,
+        nexus::{
+            self,
+            config::Config,
+            error::NexusError,
+            rate_limit::{RateLimit, RetryPolicy},
+            types::{
+                CollectionDownloadLinksResponse, CollectionRevisionData,
+                CollectionRevisionResponse, CollectionRevisionVariables, DownloadInfoRequest,
+                DownloadInfoResponse, GraphQlRequest, GraphQlResponse, ModFileInfoResponse,
+                ModFilesResponse, ModInfoResponse, UpdatedModResponse, ValidateResponse,
+            },
         },
     },
 };
@@ -24,13 +30,36 @@ pub struct NexusClient {
     client: Client,
     download_tracker: DownloadTracker,
     base_url: Url,
+    retry_policy: RetryPolicy,
 }
 
 pub const DEFAULT_NEXUS_BASE_URL: &str = "https://api.nexusmods.com/v1/";
 
+/// The GraphQL API lives next to the REST API, resolved against the base url.
+const GRAPHQL_PATH: &str = "../v2/graphql";
+
+const COLLECTION_REVISION_QUERY: &str = r#"
+query CollectionRevision($slug: String!, $domainName: String, $revision: Int) {
+  collectionRevision(slug: $slug, domainName: $domainName, revision: $revision, viewAdultContent: true) {
+    revisionNumber
+    downloadLink
+  }
+}"#;
+
 // Documentation: https://app.swaggerhub.com/apis-docs/NexusMods/nexus-mods_public_api_params_in_form_data/1.0#/
 impl NexusClient {
     pub fn new(config: &Config, download_tracker: DownloadTracker) -> anyhow::Result<Self> {
+        let (client, base_url) = Self::create_client(config)?;
+
+        Ok(Self {
+            client,
+            base_url,
+            download_tracker,
+            retry_policy: RetryPolicy::default(),
+        })
+    }
+
+    fn create_client(config: &Config) -> anyhow::Result<(Client, Url)> {
         let api_key = config
             .api_key
             .as_ref()
@@ -42,11 +71,7 @@ impl NexusClient {
             .clone()
             .unwrap_or_else(|| DEFAULT_NEXUS_BASE_URL.to_string());
 
-        Ok(Self {
-            client,
-            base_url: Url::parse(&base_url)?,
-            download_tracker,
-        })
+        Ok((client, Url::parse(&base_url)?))
     }
 
     fn create_client_with_api_key(api_key: &str) -> anyhow::Result<Client> {
@@ -84,6 +109,22 @@ impl NexusClient {
         Ok(response)
     }
 
+    /// Validates the configured key and returns the account with the rate limit Nexus reported
+    /// for it.
+    pub async fn status(config: &Config) -> anyhow::Result<(ValidateResponse, Option<RateLimit>)> {
+        let (client, base_url) = Self::create_client(config)?;
+        let retry_policy = RetryPolicy::default();
+
+        let res = retry_policy
+            .send(client.get(base_url.join("users/validate.json")?))
+            .await?;
+        let rate_limit =
+            RateLimit::from_headers(res.headers()).or_else(|| retry_policy.last_known());
+        let response = parse_json(res, "validate response").await?;
+
+        Ok((response, rate_limit))
+    }
+
     pub async fn get_download_link(
         &self,
         request: DownloadInfoRequest,
@@ -98,16 +139,20 @@ impl NexusClient {
             .join(&format!("{}/", request.file_id))?
             .join("download_link.json")?;
 
+        let mut query = Vec::new();
+        if let Some(key) = &request.key {
+            query.push(("key", key));
+        }
+        if let Some(expires) = &request.expires {
+            query.push(("expires", expires));
+        }
+
         let res = self
-            .client
-            .get(url)
-            .query(&[("key", &request.key), ("expires", &request.expires)])
-            .send()
+            .retry_policy
+            .send(self.client.get(url).query(&query))
             .await?;
 
-        let text = res.text().await?;
-
-        let response: Vec<DownloadInfoResponse> = serde_json::from_str(&text).map_err(|e| e)?;
+        let response: Vec<DownloadInfoResponse> = parse_json(res, "download link").await?;
 
         if let Some(first) = response.first() {
             Ok(first.clone())
@@ -124,11 +169,9 @@ impl NexusClient {
             .join("mods/")?
             .join(&format!("{}.json", mod_id))?;
 
-        let res = self.client.get(url).send().await?;
-
-        let file_info: ModInfoResponse = res.json().await?;
+        let res = self.retry_policy.send(self.client.get(url)).await?;
 
-        Ok(file_info)
+        parse_json(res, "mod info").await
     }
 
     pub async fn get_mod_file_info(
@@ -146,35 +189,604 @@ impl NexusClient {
             .join("files/")?
             .join(&format!("{}.json", file_id))?;
 
-        let res = self.client.get(url).send().await?;
+        let res = self.retry_policy.send(self.client.get(url)).await?;
+
+        parse_json(res, "mod file info").await
+    }
+
+    /// Mods of the game with file updates within `period` (`1d`, `1w` or `1m`).
+    pub async fn get_updated_mods(
+        &self,
+        game: &Game,
+        period: &str,
+    ) -> anyhow::Result<Vec<UpdatedModResponse>> {
+        let url = self
+            .base_url
+            .join("games/")?
+            .join(&format!("{}/", nexus::to_nexus_domain(game)?))?
+            .join("mods/")?
+            .join("updated.json")?;
 
-        let file_info: ModFileInfoResponse = res.json().await?;
+        let res = self
+            .retry_policy
+            .send(self.client.get(url).query(&[("period", period)]))
+            .await?;
+
+        parse_json(res, "updated mods").await
+    }
+
+    pub async fn get_mod_files(
+        &self,
+        game: &Game,
+        mod_id: u64,
+    ) -> anyhow::Result<ModFilesResponse> {
+        let url = self
+            .base_url
+            .join("games/")?
+            .join(&format!("{}/", nexus::to_nexus_domain(game)?))?
+            .join("mods/")?
+            .join(&format!("{}/", mod_id))?
+            .join("files.json")?;
+
+        let res = self.retry_policy.send(self.client.get(url)).await?;
+
+        parse_json(res, "mod files").await
+    }
+
+    /// Looks up a revision of a collection, the latest published one when `revision` is `None`.
+    pub async fn get_collection_revision(
+        &self,
+        slug: &str,
+        domain_name: Option<&str>,
+        revision: Option<u32>,
+    ) -> anyhow::Result<CollectionRevisionResponse> {
+        let request = GraphQlRequest {
+            query: COLLECTION_REVISION_QUERY,
+            variables: CollectionRevisionVariables {
+                slug,
+                domain_name,
+                revision,
+            },
+        };
+
+        let res = self
+            .retry_policy
+            .send(
+                self.client
+                    .post(self.base_url.join(GRAPHQL_PATH)?)
+                    .json(&request),
+            )
+            .await?;
+
+        let response: GraphQlResponse<CollectionRevisionData> =
+            parse_json(res, "collection revision").await?;
+
+        if let Some(error) = response.errors.first() {
+            bail!(
+                "Nexus could not look up collection '{}': {}",
+                slug,
+                error.message
+            );
+        }
+
+        response
+            .data
+            .and_then(|data| data.collection_revision)
+            .with_context(|| format!("Collection '{}' not found", slug))
+    }
+
+    /// Resolves the `download_link` path of a collection revision to the bundle's download url.
+    pub async fn get_collection_download_link(&self, download_link: &str) -> anyhow::Result<Url> {
+        let res = self
+            .retry_policy
+            .send(self.client.get(self.base_url.join(download_link)?))
+            .await?;
+
+        let response: CollectionDownloadLinksResponse =
+            parse_json(res, "collection download link").await?;
+        let link = response
+            .download_links
+            .first()
+            .context("Nexus returned no download link for the collection")?;
+
+        Ok(Url::parse(&link.uri)?)
+    }
+
+    /// Downloads a collection bundle, which is small enough to skip tracking and resuming.
+    pub async fn download_bundle(&self, url: &Url, output_file: &Path) -> anyhow::Result<()> {
+        let res = self
+            .client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+        let bytes = res.bytes().await?;
 
-        Ok(file_info)
+        fs::write(output_file, &bytes)
+            .with_context(|| format!("Could not write '{}'", output_file.display()))
     }
 
+    /// Downloads the file, continuing a previous partial download when the server supports range
+    /// requests. Returns the checksum of the downloaded file.
     pub async fn download_file(
         &self,
         url: &Url,
-        output_file: &PathBuf,
-        tracking_file: &PathBuf,
-    ) -> anyhow::Result<()> {
-        let res = self.client.get(url.clone()).send().await?;
-        let total_size = res.content_length().unwrap_or(0);
-
-        let stream = StreamReader::new(
-            res.bytes_stream()
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)),
+        output_file: &Path,
+        tracking_file: &Path,
+    ) -> anyhow::Result<Checksum> {
+        download::download_file(
+            &self.client,
+            &self.download_tracker,
+            url,
+            output_file,
+            tracking_file,
+        )
+        .await
+    }
+}
+
+/// Deserializes a successful response, turning error responses into a [`NexusError`].
+async fn parse_json<T: DeserializeOwned>(res: Response, what: &str) -> anyhow::Result<T> {
+    let status = res.status();
+    let text = res.text().await?;
+
+    if !status.is_success() {
+        return Err(NexusError::from_response(status, &text).into());
+    }
+
+    serde_json::from_str(&text).with_context(|| format!("Failed to deserialize {}", what))
+}
+
+#[cfg(test)]
+impl NexusClient {
+    pub fn test_with_state_file(
+        config: &Config,
+        download_tracker: DownloadTracker,
+        state_file: std::path::PathBuf,
+    ) -> anyhow::Result<Self> {
+        let mut client = Self::new(config, download_tracker)?;
+        client.retry_policy = RetryPolicy::test_with_state_file(state_file);
+
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config as MomaConfig, GameConfig},
+        games::workspace::Workspace,
+        mods::mod_list_store::ModListStore,
+        types::DownloadProgress,
+    };
+    use mockito::{Matcher, Server};
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    fn setup(base_url: &str) -> anyhow::Result<(TempDir, NexusClient)> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = MomaConfig::test_with_config(tmp_dir.path().to_owned(), game_config);
+        let workspace = Workspace::new(&game, &config)?;
+        let download_tracker =
+            DownloadTracker::new(workspace.clone(), ModListStore::new(workspace));
+
+        let nexus_config = Config {
+            api_key: Some("key".to_string()),
+            base_url: Some(base_url.to_string()),
+        };
+
+        let client = NexusClient::test_with_state_file(
+            &nexus_config,
+            download_tracker,
+            tmp_dir.path().join("rate-limit.json"),
+        )?;
+
+        Ok((tmp_dir, client))
+    }
+
+    fn write_partial(dir: &Path, content: &str, progress_bytes: u64) -> anyhow::Result<()> {
+        fs::write(dir.join("mod.7z"), content)?;
+        fs::write(
+            dir.join("1.json"),
+            serde_json::to_string(&DownloadProgress {
+                file_name: "mod.7z".to_string(),
+                progress_bytes,
+                total_bytes: 11,
+                started_at: 1,
+                updated_at: 1,
+            })?,
+        )?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_file_should_write_full_file_when_no_partial_download() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/file.7z")
+            .match_header("range", Matcher::Missing)
+            .with_body("hello world")
+            .create_async()
+            .await;
+
+        let (tmp_dir, sut) = setup(&server.url())?;
+        let output_file = tmp_dir.path().join("mod.7z");
+        let url = Url::parse(&format!("{}/file.7z", server.url()))?;
+
+        // Act
+        sut.download_file(&url, &output_file, &tmp_dir.path().join("1.json"))
+            .await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(fs::read_to_string(&output_file)?, "hello world");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_file_should_resume_when_server_returns_partial_content() -> anyhow::Result<()>
+    {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/file.7z")
+            .match_header("range", "bytes=5-")
+            .with_status(206)
+            .with_body(" world")
+            .create_async()
+            .await;
+
+        let (tmp_dir, sut) = setup(&server.url())?;
+        write_partial(tmp_dir.path(), "hello", 5)?;
+        let output_file = tmp_dir.path().join("mod.7z");
+        let url = Url::parse(&format!("{}/file.7z", server.url()))?;
+
+        // Act
+        sut.download_file(&url, &output_file, &tmp_dir.path().join("1.json"))
+            .await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(fs::read_to_string(&output_file)?, "hello world");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_file_should_restart_when_server_ignores_range() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/file.7z")
+            .match_header("range", "bytes=5-")
+            .with_status(200)
+            .with_body("hello world")
+            .create_async()
+            .await;
+
+        let (tmp_dir, sut) = setup(&server.url())?;
+        write_partial(tmp_dir.path(), "HELLO", 5)?;
+        let output_file = tmp_dir.path().join("mod.7z");
+        let url = Url::parse(&format!("{}/file.7z", server.url()))?;
+
+        // Act
+        sut.download_file(&url, &output_file, &tmp_dir.path().join("1.json"))
+            .await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(fs::read_to_string(&output_file)?, "hello world");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_file_should_restart_when_server_rejects_range() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let rejected = server
+            .mock("GET", "/file.7z")
+            .match_header("range", "bytes=5-")
+            .with_status(416)
+            .create_async()
+            .await;
+        let full = server
+            .mock("GET", "/file.7z")
+            .match_header("range", Matcher::Missing)
+            .with_body("hello world")
+            .create_async()
+            .await;
+
+        let (tmp_dir, sut) = setup(&server.url())?;
+        write_partial(tmp_dir.path(), "HELLO", 5)?;
+        let output_file = tmp_dir.path().join("mod.7z");
+        let url = Url::parse(&format!("{}/file.7z", server.url()))?;
+
+        // Act
+        sut.download_file(&url, &output_file, &tmp_dir.path().join("1.json"))
+            .await?;
+
+        // Assert
+        rejected.assert_async().await;
+        full.assert_async().await;
+        assert_eq!(fs::read_to_string(&output_file)?, "hello world");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_download_link_should_omit_key_when_not_given() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/games/skyrimspecialedition/mods/1/files/2/download_link.json",
+            )
+            .match_query(Matcher::Missing)
+            .with_body(r#"[{"URI": "https://cdn.example/file.7z"}]"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+        let request = DownloadInfoRequest {
+            game: "skyrimspecialedition".to_string(),
+            mod_id: "1".to_string(),
+            file_id: "2".to_string(),
+            key: None,
+            expires: None,
+        };
+
+        // Act
+        let actual = sut.get_download_link(request).await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(actual.uri, "https://cdn.example/file.7z");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_download_link_should_return_err_with_nexus_message_when_refused()
+    -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock(
+                "GET",
+                "/games/skyrimspecialedition/mods/1/files/2/download_link.json",
+            )
+            .with_status(403)
+            .with_body(r#"{"message": "You don't have permission to get download links"}"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+        let request = DownloadInfoRequest {
+            game: "skyrimspecialedition".to_string(),
+            mod_id: "1".to_string(),
+            file_id: "2".to_string(),
+            key: None,
+            expires: None,
+        };
+
+        // Act
+        let result = sut.get_download_link(request).await;
+
+        // Assert
+        let err = result.err().unwrap().to_string();
+        assert!(err.contains("403"));
+        assert!(err.contains("You don't have permission to get download links"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_mod_info_should_retry_when_rate_limited() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let limited = server
+            .mock("GET", "/games/skyrimspecialedition/mods/1.json")
+            .with_status(429)
+            .with_body(r#"{"message": "Too many requests"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/games/skyrimspecialedition/mods/1.json")
+            .with_body(r#"{"uid": 1, "name": "SkyUI"}"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+
+        // Act
+        let actual = sut.get_mod_info(&Game::SkyrimSE, "1").await?;
+
+        // Assert
+        limited.assert_async().await;
+        ok.assert_async().await;
+        assert_eq!(actual.name, "SkyUI");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_mod_info_should_return_err_with_nexus_message_when_not_found() -> anyhow::Result<()>
+    {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/games/skyrimspecialedition/mods/1.json")
+            .with_status(404)
+            .with_body(r#"{"message": "Mod not found"}"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+
+        // Act
+        let result = sut.get_mod_info(&Game::SkyrimSE, "1").await;
+
+        // Assert
+        let err = result.err().expect("request should fail");
+        assert_eq!(
+            err.downcast_ref::<NexusError>(),
+            Some(&NexusError::NotFound("Mod not found".to_string()))
         );
+        assert!(err.to_string().contains("404"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_mod_info_should_refuse_requests_when_daily_quota_exhausted() -> anyhow::Result<()>
+    {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/games/skyrimspecialedition/mods/1.json")
+            .with_header("X-RL-Hourly-Remaining", "0")
+            .with_header("X-RL-Daily-Remaining", "0")
+            .with_body(r#"{"uid": 1, "name": "SkyUI"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let (tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+        sut.get_mod_info(&Game::SkyrimSE, "1").await?;
+
+        // Act
+        let result = sut.get_mod_info(&Game::SkyrimSE, "1").await;
+
+        // Assert
+        mock.assert_async().await;
+        let message = result.err().map(|e| e.to_string()).unwrap_or_default();
+        assert!(message.contains("daily API quota"));
+
+        let stored = RateLimit::load(&tmp_dir.path().join("rate-limit.json"));
+        assert_eq!(stored.and_then(|r| r.daily_remaining), Some(0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_updated_mods_should_send_period_and_parse_response() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/games/skyrimspecialedition/mods/updated.json")
+            .match_query(Matcher::UrlEncoded("period".into(), "1m".into()))
+            .with_body(r#"[{"mod_id": 12604, "latest_file_update": 1700000000, "latest_mod_activity": 1700000000}]"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/", server.url()))?;
+
+        // Act
+        let actual = sut.get_updated_mods(&Game::SkyrimSE, "1m").await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].mod_id, 12604);
+        assert_eq!(actual[0].latest_file_update, 1700000000);
+
+        Ok(())
+    }
 
-        self.download_tracker
-            .stream_to_file_with_tracking(
-                stream,
-                &output_file,
-                total_size,
-                tracking_file.as_path(),
-                output_file.file_name().unwrap().display().to_string(),
+    #[tokio::test]
+    async fn get_collection_revision_should_query_graphql_next_to_rest_api() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v2/graphql")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"variables": {"slug": "qdurkx", "domainName": "skyrimspecialedition", "revision": 12}}"#
+                    .to_string(),
+            ))
+            .with_body(
+                r#"{"data": {"collectionRevision": {"revisionNumber": 12, "downloadLink": "/v1/collections/qdurkx/revisions/12/download_link"}}}"#,
             )
-            .await
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/v1/", server.url()))?;
+
+        // Act
+        let actual = sut
+            .get_collection_revision("qdurkx", Some("skyrimspecialedition"), Some(12))
+            .await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(actual.revision_number, 12);
+        assert_eq!(
+            actual.download_link,
+            "/v1/collections/qdurkx/revisions/12/download_link"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_collection_revision_should_return_err_with_graphql_error() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/v2/graphql")
+            .with_body(r#"{"data": {"collectionRevision": null}, "errors": [{"message": "Collection not published"}]}"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/v1/", server.url()))?;
+
+        // Act
+        let result = sut.get_collection_revision("qdurkx", None, None).await;
+
+        // Assert
+        let err = result.err().context("expected an error")?;
+        assert!(err.to_string().contains("Collection not published"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_collection_download_link_should_resolve_path_against_base_url()
+    -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/v1/collections/qdurkx/revisions/12/download_link")
+            .with_body(r#"{"download_links": [{"name": "CDN", "short_name": "cdn", "URI": "https://cdn.example/bundle.7z"}]}"#)
+            .create_async()
+            .await;
+
+        let (_tmp_dir, sut) = setup(&format!("{}/v1/", server.url()))?;
+
+        // Act
+        let actual = sut
+            .get_collection_download_link("/v1/collections/qdurkx/revisions/12/download_link")
+            .await?;
+
+        // Assert
+        assert_eq!(actual.as_str(), "https://cdn.example/bundle.7z");
+
+        Ok(())
     }
 }
@@ -0,0 +1,643 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, bail};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::{
+    games::Game,
+    mods::fomod::{self, Chooser, DefaultChooser, GroupType},
+    sources::nexus,
+    types::{ModLayer, PluginList},
+};
+
+/// Name of the manifest inside a collection bundle.
+pub const MANIFEST_FILE: &str = "collection.json";
+
+/// A collection given by its slug or by an `nxm://<game>/collections/<slug>/revisions/<n>` link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionLink {
+    /// Nexus game domain, only known when given as a link.
+    pub game: Option<String>,
+    pub slug: String,
+    /// Latest published revision when not given.
+    pub revision: Option<u32>,
+}
+
+pub fn parse_collection_link(input: &str) -> anyhow::Result<CollectionLink> {
+    if !input.contains("://") {
+        if input.is_empty() || !input.chars().all(|c| c.is_ascii_alphanumeric()) {
+            bail!("'{}' is not a collection slug or nxm link", input);
+        }
+
+        return Ok(CollectionLink {
+            game: None,
+            slug: input.to_string(),
+            revision: None,
+        });
+    }
+
+    let url = Url::parse(input).context("Failed to parse collection link")?;
+    if url.scheme() != "nxm" {
+        bail!("Unsupported collection link '{}', expected nxm://", input);
+    }
+
+    let game = url.host_str().context("Missing game (host)")?.to_string();
+    let segments: Vec<_> = url.path_segments().map(|c| c.collect()).unwrap_or_default();
+
+    match segments.as_slice() {
+        ["collections", slug] => Ok(CollectionLink {
+            game: Some(game),
+            slug: slug.to_string(),
+            revision: None,
+        }),
+        ["collections", slug, "revisions", revision] => Ok(CollectionLink {
+            game: Some(game),
+            slug: slug.to_string(),
+            revision: Some(
+                revision
+                    .parse()
+                    .with_context(|| format!("Invalid collection revision '{}'", revision))?,
+            ),
+        }),
+        _ => bail!("Unexpected collection link path structure"),
+    }
+}
+
+// Manifest structs, as written into collection bundles by the Nexus collection tools.
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionManifest {
+    pub info: CollectionInfo,
+    #[serde(default)]
+    pub mods: Vec<CollectionMod>,
+    #[serde(default)]
+    pub mod_rules: Vec<ModRule>,
+    #[serde(default)]
+    pub plugins: Vec<CollectionPlugin>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionInfo {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub domain_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionMod {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    /// Nexus game the mod is hosted for, collections can pull in mods of related games.
+    #[serde(default)]
+    pub domain_name: Option<String>,
+    pub source: CollectionModSource,
+    /// FOMOD options the curator picked, only set for mods with an installer.
+    #[serde(default)]
+    pub choices: Option<FomodChoices>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionModSource {
+    /// `nexus` for files hosted on Nexus, anything else is a direct download or bundled.
+    #[serde(rename = "type")]
+    pub source_type: String,
+    #[serde(default)]
+    pub mod_id: Option<u64>,
+    #[serde(default)]
+    pub file_id: Option<u64>,
+    #[serde(default)]
+    pub md5: Option<String>,
+    #[serde(default)]
+    pub logical_filename: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct FomodChoices {
+    #[serde(default)]
+    pub options: Vec<ChoiceStep>,
+}
+
+#[derive(Deserialize)]
+pub struct ChoiceStep {
+    pub name: String,
+    #[serde(default)]
+    pub groups: Vec<ChoiceGroup>,
+}
+
+#[derive(Deserialize)]
+pub struct ChoiceGroup {
+    pub name: String,
+    #[serde(default)]
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+pub struct Choice {
+    pub name: String,
+    /// Position of the option in its group, used when the name no longer matches.
+    #[serde(default)]
+    pub idx: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct ModRule {
+    /// `before`, `after`, `requires` or `conflicts`, only the first two affect the order.
+    #[serde(rename = "type")]
+    pub rule_type: String,
+    pub source: RuleReference,
+    pub reference: RuleReference,
+}
+
+#[derive(Deserialize)]
+pub struct RuleReference {
+    #[serde(default, rename = "fileMD5")]
+    pub file_md5: Option<String>,
+    #[serde(default, rename = "logicalFileName")]
+    pub logical_file_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionPlugin {
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl CollectionMod {
+    pub fn is_nexus(&self) -> bool {
+        self.source.source_type == "nexus"
+    }
+
+    /// The game to look the mod up for, the collection's game when the manifest doesn't say.
+    pub fn game(&self, collection_game: &Game) -> anyhow::Result<Game> {
+        match &self.domain_name {
+            Some(domain) => nexus::from_nexus_domain(domain),
+            None => Ok(collection_game.clone()),
+        }
+    }
+}
+
+/// Replays the FOMOD choices of the curator. Groups the manifest doesn't mention, or whose recorded
+/// options no longer exist in the installer, get the defaults of the mod author.
+pub struct ManifestChooser<'a> {
+    choices: Option<&'a FomodChoices>,
+}
+
+impl<'a> ManifestChooser<'a> {
+    pub fn new(choices: Option<&'a FomodChoices>) -> Self {
+        Self { choices }
+    }
+
+    fn recorded(&self, step: &fomod::InstallStep, group: &fomod::Group) -> Option<&'a ChoiceGroup> {
+        self.choices?
+            .options
+            .iter()
+            .filter(|s| s.name.eq_ignore_ascii_case(&step.name))
+            .flat_map(|s| s.groups.iter())
+            .find(|g| g.name.eq_ignore_ascii_case(&group.name))
+    }
+}
+
+impl Chooser for ManifestChooser<'_> {
+    fn choose(
+        &mut self,
+        step: &fomod::InstallStep,
+        group: &fomod::Group,
+        types: &[fomod::PluginType],
+    ) -> anyhow::Result<Vec<usize>> {
+        let Some(recorded) = self.recorded(step, group) else {
+            return DefaultChooser.choose(step, group, types);
+        };
+
+        let mut selection: Vec<usize> = recorded
+            .choices
+            .iter()
+            .filter_map(|choice| {
+                group
+                    .plugins
+                    .iter()
+                    .position(|p| p.name.eq_ignore_ascii_case(&choice.name))
+                    .or(choice.idx.filter(|i| *i < group.plugins.len()))
+            })
+            .collect();
+        selection.sort_unstable();
+        selection.dedup();
+
+        // The installer changed since the collection was made, an empty selection would be
+        // invalid for groups that need one option.
+        let requires_option = matches!(
+            group.group_type,
+            GroupType::SelectExactlyOne | GroupType::SelectAtLeastOne
+        );
+        if selection.is_empty() && (requires_option || !recorded.choices.is_empty()) {
+            return DefaultChooser.choose(step, group, types);
+        }
+
+        Ok(selection)
+    }
+}
+
+/// Moves the installed mods so every `before` and `after` rule holds, `a before b` meaning `b`
+/// wins conflicts. Rules referencing mods that aren't installed are ignored. `dir_for` resolves a
+/// rule reference to the mod folder it points to.
+pub fn order_by_rules<F>(layers: &mut Vec<ModLayer>, rules: &[ModRule], dir_for: F)
+where
+    F: Fn(&RuleReference) -> Option<String>,
+{
+    let ordering: Vec<(String, String)> = rules
+        .iter()
+        .filter_map(|rule| {
+            let (source, reference) = (dir_for(&rule.source)?, dir_for(&rule.reference)?);
+            match rule.rule_type.as_str() {
+                "before" => Some((source, reference)),
+                "after" => Some((reference, source)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    // Contradicting rules would move mods forever, so the number of passes is bounded.
+    for _ in 0..=ordering.len() {
+        let mut moved = false;
+
+        for (lower, higher) in &ordering {
+            let position = |name: &str| layers.iter().position(|l| l.dir_name == name);
+            let (Some(lower_index), Some(higher_index)) = (position(lower), position(higher))
+            else {
+                continue;
+            };
+
+            if lower_index > higher_index {
+                let layer = layers.remove(lower_index);
+                layers.insert(higher_index, layer);
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Sorts the plugins into the order of the collection and enables them as the curator did.
+/// Plugins the collection doesn't know keep their relative order after the known ones.
+pub fn apply_plugin_order(plugin_list: &mut PluginList, plugins: &[CollectionPlugin]) {
+    let positions: HashMap<String, (usize, bool)> = plugins
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.name.to_lowercase(), (i, p.enabled)))
+        .collect();
+
+    for plugin in plugin_list.plugins.iter_mut() {
+        if let Some((_, enabled)) = positions.get(&plugin.name.to_lowercase()) {
+            plugin.enabled = *enabled;
+        }
+    }
+
+    plugin_list.plugins.sort_by_key(|p| {
+        positions
+            .get(&p.name.to_lowercase())
+            .map_or(usize::MAX, |(i, _)| *i)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mods::fomod::{Group, GroupType, InstallStep, Plugin, PluginType, TypeDescriptor},
+        types::Plugin as GamePlugin,
+    };
+    use std::path::PathBuf;
+
+    fn group(name: &str, options: &[&str]) -> Group {
+        Group {
+            name: name.to_string(),
+            group_type: GroupType::SelectAny,
+            plugins: options
+                .iter()
+                .map(|option| Plugin {
+                    name: option.to_string(),
+                    description: String::new(),
+                    files: vec![],
+                    condition_flags: vec![],
+                    type_descriptor: TypeDescriptor {
+                        default: PluginType::Recommended,
+                        patterns: vec![],
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn step(name: &str) -> InstallStep {
+        InstallStep {
+            name: name.to_string(),
+            visible: None,
+            groups: vec![],
+        }
+    }
+
+    fn layer(dir_name: &str) -> ModLayer {
+        ModLayer {
+            dir_name: dir_name.to_string(),
+            label: dir_name.to_string(),
+            path: PathBuf::from(dir_name),
+        }
+    }
+
+    fn rule(rule_type: &str, source: &str, reference: &str) -> ModRule {
+        let reference_to = |md5: &str| RuleReference {
+            file_md5: Some(md5.to_string()),
+            logical_file_name: None,
+        };
+
+        ModRule {
+            rule_type: rule_type.to_string(),
+            source: reference_to(source),
+            reference: reference_to(reference),
+        }
+    }
+
+    #[test]
+    fn parse_collection_link_should_accept_slug() -> anyhow::Result<()> {
+        // Act
+        let actual = parse_collection_link("qdurkx")?;
+
+        // Assert
+        assert_eq!(
+            actual,
+            CollectionLink {
+                game: None,
+                slug: "qdurkx".to_string(),
+                revision: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_collection_link_should_read_game_and_revision_from_nxm_link() -> anyhow::Result<()> {
+        // Act
+        let actual =
+            parse_collection_link("nxm://skyrimspecialedition/collections/qdurkx/revisions/12")?;
+
+        // Assert
+        assert_eq!(
+            actual,
+            CollectionLink {
+                game: Some("skyrimspecialedition".to_string()),
+                slug: "qdurkx".to_string(),
+                revision: Some(12),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_collection_link_should_return_err_for_mod_file_link() -> anyhow::Result<()> {
+        // Act
+        let result =
+            parse_collection_link("nxm://skyrimspecialedition/mods/1/files/2?key=abc&expires=123");
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_should_deserialize_collection_json() -> anyhow::Result<()> {
+        // Arrange
+        let json = r#"{
+            "info": {"name": "Essentials", "author": "curator", "domainName": "skyrimspecialedition"},
+            "mods": [{
+                "name": "SkyUI",
+                "version": "5.2",
+                "optional": false,
+                "domainName": "skyrimspecialedition",
+                "source": {"type": "nexus", "modId": 12604, "fileId": 35407, "md5": "abc", "logicalFilename": "SkyUI"},
+                "choices": {"type": "fomod", "options": [{"name": "Main", "groups": [{"name": "Style", "choices": [{"name": "Dark", "idx": 1}]}]}]}
+            }],
+            "modRules": [{"type": "before", "source": {"fileMD5": "abc"}, "reference": {"logicalFileName": "Patch"}}],
+            "plugins": [{"name": "SkyUI_SE.esp", "enabled": true}]
+        }"#;
+
+        // Act
+        let actual: CollectionManifest = serde_json::from_str(json)?;
+
+        // Assert
+        assert_eq!(actual.info.name, "Essentials");
+        assert!(actual.mods[0].is_nexus());
+        assert_eq!(
+            actual.mods[0].domain_name.as_deref(),
+            Some("skyrimspecialedition")
+        );
+        assert_eq!(actual.mods[0].source.file_id, Some(35407));
+        assert_eq!(
+            actual.mods[0].choices.as_ref().unwrap().options[0].groups[0].choices[0].name,
+            "Dark"
+        );
+        assert_eq!(actual.mod_rules[0].source.file_md5.as_deref(), Some("abc"));
+        assert_eq!(
+            actual.mod_rules[0].reference.logical_file_name.as_deref(),
+            Some("Patch")
+        );
+        assert_eq!(actual.plugins[0].name, "SkyUI_SE.esp");
+
+        Ok(())
+    }
+
+    #[test]
+    fn game_should_use_domain_of_mod_over_collection_game() -> anyhow::Result<()> {
+        // Arrange
+        let json = r#"[
+            {"name": "A", "domainName": "skyrim", "source": {"type": "nexus"}},
+            {"name": "B", "source": {"type": "nexus"}}
+        ]"#;
+        let mods: Vec<CollectionMod> = serde_json::from_str(json)?;
+
+        // Act
+        let actual: Vec<Game> = mods
+            .iter()
+            .map(|m| m.game(&Game::SkyrimSE))
+            .collect::<anyhow::Result<_>>()?;
+
+        // Assert
+        assert_eq!(actual[0].id(), Game::SkyrimLE.id());
+        assert_eq!(actual[1].id(), Game::SkyrimSE.id());
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_chooser_should_select_recorded_options_by_name() -> anyhow::Result<()> {
+        // Arrange
+        let choices = FomodChoices {
+            options: vec![ChoiceStep {
+                name: "Main".to_string(),
+                groups: vec![ChoiceGroup {
+                    name: "Style".to_string(),
+                    choices: vec![Choice {
+                        name: "Dark".to_string(),
+                        idx: Some(0),
+                    }],
+                }],
+            }],
+        };
+        let mut sut = ManifestChooser::new(Some(&choices));
+        let types = [PluginType::Recommended; 3];
+
+        // Act
+        let actual = sut.choose(
+            &step("Main"),
+            &group("Style", &["Light", "Dark", "Blue"]),
+            &types,
+        )?;
+
+        // Assert
+        assert_eq!(actual, vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_chooser_should_fall_back_to_defaults_for_unknown_group() -> anyhow::Result<()> {
+        // Arrange
+        let mut sut = ManifestChooser::new(None);
+        let types = [PluginType::Recommended, PluginType::Recommended];
+
+        // Act
+        let actual = sut.choose(&step("Main"), &group("Patches", &["A", "B"]), &types)?;
+
+        // Assert
+        assert_eq!(actual, vec![0, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_chooser_should_fall_back_to_defaults_when_recorded_options_are_gone()
+    -> anyhow::Result<()> {
+        // Arrange
+        let choices = FomodChoices {
+            options: vec![ChoiceStep {
+                name: "Main".to_string(),
+                groups: vec![ChoiceGroup {
+                    name: "Style".to_string(),
+                    choices: vec![Choice {
+                        name: "Removed".to_string(),
+                        idx: Some(5),
+                    }],
+                }],
+            }],
+        };
+        let mut sut = ManifestChooser::new(Some(&choices));
+        let types = [PluginType::Optional, PluginType::Recommended];
+        let mut style = group("Style", &["Light", "Dark"]);
+        style.group_type = GroupType::SelectExactlyOne;
+
+        // Act
+        let actual = sut.choose(&step("Main"), &style, &types)?;
+
+        // Assert
+        assert_eq!(actual, vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn order_by_rules_should_move_mods_to_satisfy_rules() -> anyhow::Result<()> {
+        // Arrange
+        let mut layers = vec![layer("patch"), layer("base"), layer("other")];
+        let rules = vec![
+            rule("before", "base", "patch"),
+            rule("after", "other", "patch"),
+            rule("requires", "patch", "base"),
+        ];
+
+        // Act
+        order_by_rules(&mut layers, &rules, |r| r.file_md5.clone());
+
+        // Assert
+        let actual: Vec<_> = layers.iter().map(|l| l.dir_name.as_str()).collect();
+        assert_eq!(actual, vec!["base", "patch", "other"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn order_by_rules_should_stop_when_rules_contradict() -> anyhow::Result<()> {
+        // Arrange
+        let mut layers = vec![layer("a"), layer("b")];
+        let rules = vec![rule("before", "a", "b"), rule("before", "b", "a")];
+
+        // Act
+        order_by_rules(&mut layers, &rules, |r| r.file_md5.clone());
+
+        // Assert
+        assert_eq!(layers.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_plugin_order_should_follow_collection_and_keep_unknown_plugins_last()
+    -> anyhow::Result<()> {
+        // Arrange
+        let plugin = |name: &str, enabled: bool| GamePlugin {
+            name: name.to_string(),
+            enabled,
+        };
+        let mut plugin_list = PluginList {
+            plugins: vec![
+                plugin("Mine.esp", true),
+                plugin("B.esp", true),
+                plugin("A.esp", true),
+            ],
+        };
+        let plugins = vec![
+            CollectionPlugin {
+                name: "a.esp".to_string(),
+                enabled: true,
+            },
+            CollectionPlugin {
+                name: "B.esp".to_string(),
+                enabled: false,
+            },
+        ];
+
+        // Act
+        apply_plugin_order(&mut plugin_list, &plugins);
+
+        // Assert
+        let actual: Vec<_> = plugin_list
+            .plugins
+            .iter()
+            .map(|p| (p.name.as_str(), p.enabled))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![("A.esp", true), ("B.esp", false), ("Mine.esp", true)]
+        );
+
+        Ok(())
+    }
+}
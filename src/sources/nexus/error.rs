@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use reqwest::StatusCode;
+
+use crate::{sources::nexus::types::ErrorResponse, usage_for};
+
+/// Why Nexus refused a request, built from the response status and its `message` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NexusError {
+    /// The API key is missing, invalid or was revoked.
+    Unauthorized(String),
+    /// The account may not use this endpoint, e.g. download links without premium.
+    Forbidden(String),
+    /// The mod or file doesn't exist or was hidden by its author.
+    NotFound(String),
+    /// The key and expiry from the nxm link are no longer valid.
+    Expired(String),
+    RateLimited(String),
+    Server(u16, String),
+    /// Any other status Nexus isn't documented to return.
+    Other(u16, String),
+}
+
+impl NexusError {
+    pub fn from_response(status: StatusCode, body: &str) -> Self {
+        let message = serde_json::from_str::<ErrorResponse>(body)
+            .map(|e| e.message)
+            .unwrap_or_else(|_| body.trim().to_string());
+
+        // Nexus answers expired nxm links with 410, some endpoints use 403 with a message instead.
+        if status == StatusCode::GONE || message.to_lowercase().contains("expired") {
+            return Self::Expired(message);
+        }
+
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(message),
+            StatusCode::FORBIDDEN => Self::Forbidden(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(message),
+            s if s.is_server_error() => Self::Server(s.as_u16(), message),
+            s => Self::Other(s.as_u16(), message),
+        }
+    }
+
+    /// What the user can do about it, short enough for a desktop notification.
+    pub fn hint(&self) -> String {
+        match self {
+            NexusError::Unauthorized(_) => {
+                format!("Your Nexus API key was rejected, run '{}' to set a new one.", usage_for!("connect"))
+            }
+            NexusError::Forbidden(_) => {
+                "Nexus denied access. Without premium, use 'Mod Manager Download' on the mod page."
+                    .to_string()
+            }
+            NexusError::NotFound(_) => {
+                "The mod or file was not found, it may have been hidden or removed by its author."
+                    .to_string()
+            }
+            NexusError::Expired(_) => {
+                "The download link has expired, click 'Mod Manager Download' on the mod page again."
+                    .to_string()
+            }
+            NexusError::RateLimited(_) => {
+                "Nexus rate limit reached, try again later. 'moma connect nexus --status' shows what is left."
+                    .to_string()
+            }
+            NexusError::Server(_, _) => {
+                "Nexus is having problems right now, try the download again later.".to_string()
+            }
+            NexusError::Other(_, _) => "Nexus refused the request.".to_string(),
+        }
+    }
+}
+
+impl Display for NexusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NexusError::Unauthorized(message) => {
+                write!(f, "Nexus rejected the API key (401): {}", message)
+            }
+            NexusError::Forbidden(message) => write!(f, "Nexus denied access (403): {}", message),
+            NexusError::NotFound(message) => write!(f, "Nexus found nothing (404): {}", message),
+            NexusError::Expired(message) => write!(f, "Nexus link expired: {}", message),
+            NexusError::RateLimited(message) => {
+                write!(f, "Nexus rate limit reached (429): {}", message)
+            }
+            NexusError::Server(status, message) => {
+                write!(f, "Nexus server error ({}): {}", status, message)
+            }
+            NexusError::Other(status, message) => {
+                write!(f, "Nexus refused the request ({}): {}", status, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NexusError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_response_should_use_message_from_json_body() -> anyhow::Result<()> {
+        // Act
+        let actual =
+            NexusError::from_response(StatusCode::NOT_FOUND, r#"{"message": "Mod not found"}"#);
+
+        // Assert
+        assert_eq!(actual, NexusError::NotFound("Mod not found".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_response_should_return_expired_when_message_mentions_expiry() -> anyhow::Result<()> {
+        // Act
+        let actual = NexusError::from_response(
+            StatusCode::FORBIDDEN,
+            r#"{"message": "This link has expired"}"#,
+        );
+
+        // Assert
+        assert_eq!(
+            actual,
+            NexusError::Expired("This link has expired".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_response_should_keep_raw_body_when_not_json() -> anyhow::Result<()> {
+        // Act
+        let actual =
+            NexusError::from_response(StatusCode::BAD_GATEWAY, "<html>Bad gateway</html>\n");
+
+        // Assert
+        assert_eq!(
+            actual,
+            NexusError::Server(502, "<html>Bad gateway</html>".to_string())
+        );
+
+        Ok(())
+    }
+}
@@ -1,11 +1,18 @@
 mod api;
 mod client;
+pub mod collection;
 mod config;
+mod error;
+mod rate_limit;
 mod setup;
 mod types;
+mod updates;
 
 pub use api::{Nexus, NxmLink};
 pub use config::Config;
+pub use error::NexusError;
+pub use rate_limit::RateLimit;
 pub use setup::{
     configure_nxm_link_handler, from_nexus_domain, parse_nxm_url, resolve_api_key, to_nexus_domain,
 };
+pub use updates::{ArchiveUpdate, NewerFile, changelog_snippet};
@@ -0,0 +1,358 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, bail};
+use reqwest::{
+    RequestBuilder, Response, StatusCode,
+    header::{self, HeaderMap},
+};
+use serde::{Deserialize, Serialize};
+
+const RATE_LIMIT_FILE_NAME: &str = "nexus/rate-limit.json";
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const MAX_RETRIES: u32 = 3;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Remaining API requests as last reported by Nexus in the `X-RL-*` response headers.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    pub hourly_limit: Option<u64>,
+    pub hourly_remaining: Option<u64>,
+    pub hourly_reset: Option<String>,
+    pub daily_limit: Option<u64>,
+    pub daily_remaining: Option<u64>,
+    pub daily_reset: Option<String>,
+    /// Seconds since the Unix epoch when these values were received.
+    pub updated_at: u64,
+}
+
+impl RateLimit {
+    /// Reads the rate-limit headers, returns `None` when the response carried none of them.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let text = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+        };
+        let number = |name: &str| text(name).and_then(|v| v.parse::<u64>().ok());
+
+        let rate_limit = Self {
+            hourly_limit: number("x-rl-hourly-limit"),
+            hourly_remaining: number("x-rl-hourly-remaining"),
+            hourly_reset: text("x-rl-hourly-reset"),
+            daily_limit: number("x-rl-daily-limit"),
+            daily_remaining: number("x-rl-daily-remaining"),
+            daily_reset: text("x-rl-daily-reset"),
+            updated_at: now(),
+        };
+
+        if rate_limit.hourly_remaining.is_none() && rate_limit.daily_remaining.is_none() {
+            return None;
+        }
+
+        Some(rate_limit)
+    }
+
+    /// Seconds until the daily quota resets when it is used up, `None` while requests are left.
+    /// Uses the reset time Nexus sent, or the next midnight UTC when it's missing or unreadable.
+    pub fn daily_exhausted_for(&self, now: u64) -> Option<u64> {
+        if self.daily_remaining != Some(0) {
+            return None;
+        }
+
+        let reset_at = self
+            .daily_reset
+            .as_deref()
+            .and_then(parse_reset_time)
+            .unwrap_or((self.updated_at / SECONDS_PER_DAY + 1) * SECONDS_PER_DAY);
+        (now < reset_at).then(|| reset_at - now)
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+/// Sends API requests, backing off exponentially while Nexus answers with 429 or a server error
+/// and refusing to send anything while the daily quota is used up.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Where the last known rate limit is kept, so every moma process knows about the quota.
+    state_file: Option<PathBuf>,
+    base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            state_file: crate::config::Config::resolve_config_file_path(RATE_LIMIT_FILE_NAME),
+            base_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub async fn send(&self, request: RequestBuilder) -> anyhow::Result<Response> {
+        self.ensure_quota_left()?;
+
+        let mut delay = self.base_delay;
+        let mut attempt = 0;
+
+        loop {
+            let res = request
+                .try_clone()
+                .context("Request can't be retried")?
+                .send()
+                .await?;
+
+            if let Some(rate_limit) = RateLimit::from_headers(res.headers()) {
+                self.store(&rate_limit);
+            }
+
+            let status = res.status();
+            let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if !retryable || attempt >= MAX_RETRIES {
+                return Ok(res);
+            }
+
+            // No point in waiting for a quota that only resets the next day.
+            self.ensure_quota_left()?;
+
+            let wait = retry_after(res.headers())
+                .unwrap_or(delay)
+                .min(MAX_RETRY_DELAY);
+            log::warn!("Nexus responded with {}, retrying in {:?}", status, wait);
+            tokio::time::sleep(wait).await;
+
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// The rate limit Nexus reported with the most recent response.
+    pub fn last_known(&self) -> Option<RateLimit> {
+        self.state_file.as_deref().and_then(RateLimit::load)
+    }
+
+    fn ensure_quota_left(&self) -> anyhow::Result<()> {
+        let Some(seconds) = self
+            .last_known()
+            .and_then(|rate_limit| rate_limit.daily_exhausted_for(now()))
+        else {
+            return Ok(());
+        };
+
+        bail!(
+            "Nexus daily API quota is used up, it resets in {}h {}m.",
+            seconds / 3_600,
+            seconds % 3_600 / 60
+        )
+    }
+
+    fn store(&self, rate_limit: &RateLimit) {
+        if let Some(path) = &self.state_file
+            && let Err(e) = rate_limit.save(path)
+        {
+            log::warn!("Could not store Nexus rate limit: {:#}", e);
+        }
+    }
+}
+
+/// Seconds since the Unix epoch for a reset time like `2025-07-01 00:00:00 +0000`, the format of
+/// the `X-RL-*-Reset` headers. The ISO 8601 form with `T` and `Z` or `+00:00` is accepted too.
+fn parse_reset_time(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (date, rest) = value.split_at_checked(10)?;
+    let rest = rest.strip_prefix([' ', 'T'])?;
+    let (time, offset) = rest.split_at_checked(8)?;
+
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let mut time = time.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    let offset = match offset.trim() {
+        "" | "Z" => 0,
+        offset => {
+            let sign = match offset.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            let digits = offset[1..].replace(':', "");
+            if digits.len() != 4 {
+                return None;
+            }
+            let (hours, minutes) = digits.split_at(2);
+            sign * (hours.parse::<i64>().ok()? * 3_600 + minutes.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY as i64
+        + hour * 3_600
+        + minute * 60
+        + second
+        - offset;
+    u64::try_from(seconds).ok()
+}
+
+/// Days since 1970-01-01 for a date in the proleptic Gregorian calendar, following Howard
+/// Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Delay requested by the server in a `Retry-After` header given in seconds.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+impl RetryPolicy {
+    pub fn test_with_state_file(state_file: PathBuf) -> Self {
+        Self {
+            state_file: Some(state_file),
+            base_delay: Duration::from_millis(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn from_headers_should_parse_rate_limit_headers() -> anyhow::Result<()> {
+        // Arrange
+        let mut headers = HeaderMap::new();
+        headers.insert("X-RL-Hourly-Limit", HeaderValue::from_static("500"));
+        headers.insert("X-RL-Hourly-Remaining", HeaderValue::from_static("499"));
+        headers.insert("X-RL-Daily-Limit", HeaderValue::from_static("20000"));
+        headers.insert("X-RL-Daily-Remaining", HeaderValue::from_static("19876"));
+        headers.insert(
+            "X-RL-Daily-Reset",
+            HeaderValue::from_static("2025-07-01 00:00:00 +0000"),
+        );
+
+        // Act
+        let actual = RateLimit::from_headers(&headers);
+
+        // Assert
+        let actual = actual.expect("rate limit should be parsed");
+        assert_eq!(actual.hourly_limit, Some(500));
+        assert_eq!(actual.hourly_remaining, Some(499));
+        assert_eq!(actual.daily_remaining, Some(19876));
+        assert_eq!(
+            actual.daily_reset.as_deref(),
+            Some("2025-07-01 00:00:00 +0000")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_headers_should_return_none_when_headers_missing() -> anyhow::Result<()> {
+        // Arrange
+        let headers = HeaderMap::new();
+
+        // Act
+        let actual = RateLimit::from_headers(&headers);
+
+        // Assert
+        assert!(actual.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn daily_exhausted_for_should_return_time_until_midnight_utc() -> anyhow::Result<()> {
+        // Arrange
+        let sut = RateLimit {
+            daily_remaining: Some(0),
+            updated_at: 10 * SECONDS_PER_DAY + 3_600,
+            ..Default::default()
+        };
+
+        // Act
+        let before_reset = sut.daily_exhausted_for(10 * SECONDS_PER_DAY + 7_200);
+        let after_reset = sut.daily_exhausted_for(11 * SECONDS_PER_DAY);
+
+        // Assert
+        assert_eq!(before_reset, Some(SECONDS_PER_DAY - 7_200));
+        assert_eq!(after_reset, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn daily_exhausted_for_should_use_reset_time_from_nexus() -> anyhow::Result<()> {
+        // Arrange
+        // 2025-07-01 00:00:00 UTC
+        let reset_at = 1_751_328_000;
+        let sut = RateLimit {
+            daily_remaining: Some(0),
+            daily_reset: Some("2025-07-01 00:00:00 +0200".to_string()),
+            updated_at: reset_at - SECONDS_PER_DAY,
+            ..Default::default()
+        };
+
+        // Act
+        let actual = sut.daily_exhausted_for(reset_at - 10 * 3_600);
+
+        // Assert
+        assert_eq!(actual, Some(8 * 3_600));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_reset_time_should_accept_nexus_and_iso_formats() -> anyhow::Result<()> {
+        // Arrange
+        let expected = Some(1_751_328_000);
+
+        // Act & Assert
+        assert_eq!(parse_reset_time("2025-07-01 00:00:00 +0000"), expected);
+        assert_eq!(parse_reset_time("2025-07-01T00:00:00Z"), expected);
+        assert_eq!(parse_reset_time("2025-07-01T02:00:00+02:00"), expected);
+        assert_eq!(parse_reset_time("2025-06-30 19:00:00 -0500"), expected);
+        assert_eq!(parse_reset_time("tomorrow"), None);
+
+        Ok(())
+    }
+}
@@ -5,9 +5,11 @@ use owo_colors::OwoColorize;
 use reqwest::Url;
 
 use crate::{
+    cli::Cli,
     games::Game,
     sources::nexus::{self, api::NxmLink, client::NexusClient},
     ui::prompt,
+    usage_for,
 };
 
 pub async fn resolve_api_key() -> anyhow::Result<String> {
@@ -81,6 +83,13 @@ pub fn parse_nxm_url(link: &str) -> anyhow::Result<NxmLink> {
     let game = url.host_str().context("Missing game (host)")?.to_string();
 
     let segments: Vec<_> = url.path_segments().map(|c| c.collect()).unwrap_or_default();
+    if segments.first() == Some(&"collections") {
+        bail!(
+            "Collection links are installed with '{}'",
+            usage_for!(Cli::COLLECTION, Cli::COLLECTION_INSTALL)
+        );
+    }
+
     if segments.len() < 4 {
         bail!("Invalid NXM URL format");
     }
@@ -113,9 +122,10 @@ pub fn parse_nxm_url(link: &str) -> anyhow::Result<NxmLink> {
 
 pub fn from_nexus_domain(domain: &str) -> anyhow::Result<Game> {
     match domain.to_lowercase().as_str() {
-        "skyrimspecialedition" => {
-            Game::from_id("skyrimse").ok_or_else(|| anyhow::anyhow!("Invalid internal game ID"))
-        }
+        "skyrimspecialedition" => Ok(Game::SkyrimSE),
+        "skyrim" => Ok(Game::SkyrimLE),
+        "fallout4" => Ok(Game::Fallout4),
+        "newvegas" => Ok(Game::FalloutNV),
         _ => bail!("Unsupported game domain: {}", domain),
     }
 }
@@ -123,5 +133,52 @@ pub fn from_nexus_domain(domain: &str) -> anyhow::Result<Game> {
 pub fn to_nexus_domain(game: &Game) -> anyhow::Result<&'static str> {
     match game {
         Game::SkyrimSE => Ok("skyrimspecialedition"),
+        Game::SkyrimLE => Ok("skyrim"),
+        Game::Fallout4 => Ok("fallout4"),
+        Game::FalloutNV => Ok("newvegas"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn from_nexus_domain_should_return_game_for_each_domain() -> anyhow::Result<()> {
+        for (domain, id) in [
+            ("skyrimspecialedition", "skyrimse"),
+            ("skyrim", "skyrimle"),
+            ("fallout4", "fallout4"),
+            ("newvegas", "falloutnv"),
+        ] {
+            // Act
+            let actual = from_nexus_domain(domain)?;
+
+            // Assert
+            assert_eq!(actual.id(), id);
+            assert_eq!(to_nexus_domain(&actual)?, domain);
+        }
+
+        assert!(from_nexus_domain("oblivion").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_nxm_url_should_accept_links_of_each_game() -> anyhow::Result<()> {
+        for game in Game::iter() {
+            // Arrange
+            let domain = to_nexus_domain(&game)?;
+            let url = format!("nxm://{}/mods/1/files/2?key=abc&expires=123", domain);
+
+            // Act
+            let actual = parse_nxm_url(&url)?;
+
+            // Assert
+            assert_eq!(from_nexus_domain(&actual.game)?.id(), game.id());
+        }
+
+        Ok(())
     }
 }
@@ -1,5 +1,13 @@
-use crate::types::{FileStatus, Mod, ModArchive};
-use serde::Deserialize;
+use crate::{
+    sources::Source,
+    types::{FileStatus, Mod, ModArchive},
+};
+use serde::{Deserialize, Serialize};
+
+/// Nexus uids combine the game id in the upper and the mod or file id in the lower 32 bits.
+pub fn id_from_uid(uid: u64) -> u64 {
+    uid & 0xFFFF_FFFF
+}
 
 // Request structs
 
@@ -7,8 +15,23 @@ pub struct DownloadInfoRequest {
     pub game: String,
     pub mod_id: String,
     pub file_id: String,
-    pub key: String,
-    pub expires: String,
+    /// Only required for non-premium accounts, it comes from the nxm link on the website.
+    pub key: Option<String>,
+    pub expires: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GraphQlRequest<'a, V> {
+    pub query: &'a str,
+    pub variables: V,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRevisionVariables<'a> {
+    pub slug: &'a str,
+    pub domain_name: Option<&'a str>,
+    pub revision: Option<u32>,
 }
 
 // Response structs
@@ -16,6 +39,15 @@ pub struct DownloadInfoRequest {
 #[derive(Deserialize)]
 pub struct ValidateResponse {
     pub key: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub is_premium: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    pub message: String,
 }
 
 #[derive(Deserialize, Clone)]
@@ -24,16 +56,85 @@ pub struct DownloadInfoResponse {
     pub uri: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct ModFileInfoResponse {
     pub uid: u64,
+    #[serde(default)]
+    pub file_id: u64,
     pub file_name: String,
+    #[serde(default)]
+    pub md5: Option<String>,
+    #[serde(default)]
+    pub size_in_bytes: Option<u64>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub category_name: Option<String>,
+    #[serde(default)]
+    pub uploaded_timestamp: Option<u64>,
+    #[serde(default)]
+    pub changelog_html: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct ModInfoResponse {
     pub uid: u64,
     pub name: String,
+    #[serde(default)]
+    pub mod_id: Option<u64>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub category_id: Option<u64>,
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ModFilesResponse {
+    pub files: Vec<ModFileInfoResponse>,
+    #[serde(default)]
+    pub file_updates: Vec<FileUpdateResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct FileUpdateResponse {
+    pub old_file_id: u64,
+    pub new_file_id: u64,
+}
+
+#[derive(Deserialize)]
+pub struct UpdatedModResponse {
+    pub mod_id: u64,
+    pub latest_file_update: u64,
+}
+
+#[derive(Deserialize)]
+pub struct GraphQlResponse<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<ErrorResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRevisionData {
+    pub collection_revision: Option<CollectionRevisionResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRevisionResponse {
+    pub revision_number: u32,
+    /// Path of the REST endpoint that hands out the bundle download link.
+    pub download_link: String,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionDownloadLinksResponse {
+    pub download_links: Vec<DownloadInfoResponse>,
 }
 
 impl From<ModFileInfoResponse> for ModArchive {
@@ -43,6 +144,13 @@ impl From<ModFileInfoResponse> for ModArchive {
             archive_path: None,
             file_name: value.file_name,
             status: FileStatus::Unknown,
+            md5: value.md5.filter(|m| !m.is_empty()),
+            size: value.size_in_bytes,
+            version: value.version.filter(|v| !v.is_empty()),
+            uploaded_at: value.uploaded_timestamp,
+            category: value.category_name.filter(|c| !c.is_empty()),
+            sha256: None,
+            url: None,
         }
     }
 }
@@ -53,6 +161,14 @@ impl From<ModInfoResponse> for Mod {
             name: value.name,
             uid: value.uid,
             archives: vec![],
+            mod_id: value.mod_id,
+            author: value.author.filter(|a| !a.is_empty()),
+            version: value.version.filter(|v| !v.is_empty()),
+            category_id: value.category_id,
+            summary: value.summary.filter(|s| !s.is_empty()),
+            source: Some(Source::Nexus),
+            repository: None,
+            asset_pattern: None,
         }
     }
 }
@@ -0,0 +1,251 @@
+use crate::{
+    sources::nexus::types::{ModFileInfoResponse, ModFilesResponse, id_from_uid},
+    types::{FileStatus, Mod},
+};
+
+/// File categories Nexus moves superseded files into.
+const OUTDATED_CATEGORIES: [&str; 2] = ["OLD_VERSION", "ARCHIVED"];
+
+const CHANGELOG_SNIPPET_LENGTH: usize = 200;
+
+/// What changed on Nexus for one of our archives.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ArchiveUpdate {
+    pub mod_name: String,
+    pub file_name: String,
+    pub version: Option<String>,
+    /// The file that replaced ours, if the author linked one.
+    pub newer_file: Option<NewerFile>,
+    /// Set when the author moved our file to an outdated category.
+    pub outdated_category: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewerFile {
+    pub file_name: String,
+    pub version: Option<String>,
+    pub uploaded_at: Option<u64>,
+    pub changelog: Option<String>,
+}
+
+/// Compares the downloaded and installed archives of a mod with its files on Nexus.
+pub fn find_updates(m: &Mod, files: &ModFilesResponse) -> Vec<ArchiveUpdate> {
+    m.archives
+        .iter()
+        .filter(|a| matches!(a.status, FileStatus::Downloaded | FileStatus::Installed))
+        .filter_map(|archive| {
+            let current = files.files.iter().find(|f| f.uid == archive.file_uid);
+            let file_id = current.map_or(id_from_uid(archive.file_uid), |f| f.file_id);
+
+            let newer_file = latest_replacement(files, file_id).map(|f| NewerFile {
+                file_name: f.file_name.clone(),
+                version: f.version.clone().filter(|v| !v.is_empty()),
+                uploaded_at: f.uploaded_timestamp,
+                changelog: f.changelog_html.as_deref().and_then(changelog_snippet),
+            });
+
+            let outdated_category = current
+                .and_then(|f| f.category_name.clone())
+                .filter(|c| OUTDATED_CATEGORIES.contains(&c.as_str()));
+
+            if newer_file.is_none() && outdated_category.is_none() {
+                return None;
+            }
+
+            Some(ArchiveUpdate {
+                mod_name: m.name.clone(),
+                file_name: archive.file_name.clone(),
+                version: archive.version.clone(),
+                newer_file,
+                outdated_category,
+            })
+        })
+        .collect()
+}
+
+/// Follows the chain of file updates from `file_id` to the newest file that replaced it.
+fn latest_replacement(files: &ModFilesResponse, file_id: u64) -> Option<&ModFileInfoResponse> {
+    let mut current = file_id;
+    let mut seen = vec![current];
+
+    while let Some(update) = files.file_updates.iter().find(|u| u.old_file_id == current) {
+        // Authors can link files back and forth, stop instead of looping.
+        if seen.contains(&update.new_file_id) {
+            break;
+        }
+        current = update.new_file_id;
+        seen.push(current);
+    }
+
+    if current == file_id {
+        return None;
+    }
+
+    files.files.iter().find(|f| f.file_id == current)
+}
+
+/// First part of a changelog with the HTML tags removed.
+pub fn changelog_snippet(html: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+
+    if text.chars().count() <= CHANGELOG_SNIPPET_LENGTH {
+        return Some(text);
+    }
+
+    let snippet: String = text.chars().take(CHANGELOG_SNIPPET_LENGTH).collect();
+    Some(format!("{}...", snippet.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sources::nexus::types::FileUpdateResponse, types::ModArchive};
+
+    const GAME: u64 = 1704 << 32;
+
+    fn file(file_id: u64, category: &str) -> ModFileInfoResponse {
+        ModFileInfoResponse {
+            uid: GAME + file_id,
+            file_id,
+            file_name: format!("file-{}.7z", file_id),
+            md5: None,
+            size_in_bytes: None,
+            version: Some(format!("1.{}", file_id)),
+            category_name: Some(category.to_string()),
+            uploaded_timestamp: Some(file_id * 100),
+            changelog_html: Some(format!("<ul><li>Fixed bug {}</li></ul>", file_id)),
+        }
+    }
+
+    fn installed_mod(file_id: u64) -> Mod {
+        Mod {
+            uid: GAME + 1,
+            name: "SkyUI".to_string(),
+            archives: vec![ModArchive {
+                file_uid: GAME + file_id,
+                file_name: format!("file-{}.7z", file_id),
+                status: FileStatus::Installed,
+                version: Some(format!("1.{}", file_id)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn find_updates_should_follow_file_updates_to_newest_file() -> anyhow::Result<()> {
+        // Arrange
+        let files = ModFilesResponse {
+            files: vec![
+                file(1, "OLD_VERSION"),
+                file(2, "OLD_VERSION"),
+                file(3, "MAIN"),
+            ],
+            file_updates: vec![
+                FileUpdateResponse {
+                    old_file_id: 1,
+                    new_file_id: 2,
+                },
+                FileUpdateResponse {
+                    old_file_id: 2,
+                    new_file_id: 3,
+                },
+            ],
+        };
+
+        // Act
+        let actual = find_updates(&installed_mod(1), &files);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![ArchiveUpdate {
+                mod_name: "SkyUI".to_string(),
+                file_name: "file-1.7z".to_string(),
+                version: Some("1.1".to_string()),
+                newer_file: Some(NewerFile {
+                    file_name: "file-3.7z".to_string(),
+                    version: Some("1.3".to_string()),
+                    uploaded_at: Some(300),
+                    changelog: Some("Fixed bug 3".to_string()),
+                }),
+                outdated_category: Some("OLD_VERSION".to_string()),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_updates_should_flag_archived_file_without_replacement() -> anyhow::Result<()> {
+        // Arrange
+        let files = ModFilesResponse {
+            files: vec![file(1, "ARCHIVED")],
+            file_updates: vec![],
+        };
+
+        // Act
+        let actual = find_updates(&installed_mod(1), &files);
+
+        // Assert
+        assert_eq!(actual.len(), 1);
+        assert!(actual[0].newer_file.is_none());
+        assert_eq!(actual[0].outdated_category.as_deref(), Some("ARCHIVED"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_updates_should_return_empty_when_file_is_current() -> anyhow::Result<()> {
+        // Arrange
+        let files = ModFilesResponse {
+            files: vec![file(1, "MAIN")],
+            file_updates: vec![FileUpdateResponse {
+                old_file_id: 1,
+                new_file_id: 1,
+            }],
+        };
+
+        // Act
+        let actual = find_updates(&installed_mod(1), &files);
+
+        // Assert
+        assert!(actual.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn changelog_snippet_should_strip_tags_and_truncate() -> anyhow::Result<()> {
+        // Arrange
+        let html = format!("<p>{}</p>", "word ".repeat(100));
+
+        // Act
+        let actual = changelog_snippet(&html).unwrap();
+
+        // Assert
+        assert!(actual.starts_with("word word"));
+        assert!(actual.ends_with("..."));
+        assert!(!actual.contains('<'));
+
+        Ok(())
+    }
+}
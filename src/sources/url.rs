@@ -0,0 +1,372 @@
+use std::path::Path;
+
+use anyhow::{Context, bail};
+use reqwest::{Client, Url};
+
+use crate::{
+    mods::{download_tracker::DownloadTracker, mod_list_store::ModListStore},
+    sources::{Source, download},
+    types::{FileStatus, Mod, ModArchive},
+    utils::fs::{ARCHIVE_EXTENSIONS, is_supported_archive},
+};
+
+/// Downloads archives from plain http(s) urls, e.g. script extenders or ENB binaries.
+pub struct UrlDownloader {
+    client: Client,
+    download_tracker: DownloadTracker,
+}
+
+impl UrlDownloader {
+    pub fn new(download_tracker: DownloadTracker) -> anyhow::Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!("moma/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            download_tracker,
+        })
+    }
+
+    /// Downloads the archive with tracking and stages it as a new mod named `name`. A url that
+    /// failed before is downloaded again into the same mod. When `sha256` is given the download
+    /// has to match it.
+    pub async fn fetch(
+        &self,
+        mod_list_store: &ModListStore,
+        name: &str,
+        url: &Url,
+        sha256: Option<&str>,
+    ) -> anyhow::Result<(Mod, ModArchive)> {
+        let file_name = archive_name(url)?;
+
+        let existing = mod_list_store.read()?.mods.into_iter().find_map(|m| {
+            let archive = m
+                .archives
+                .iter()
+                .find(|a| a.url.as_deref() == Some(url.as_str()))
+                .cloned();
+            archive.map(|a| (m, a))
+        });
+
+        let (m, archive) = match existing {
+            Some((m, archive))
+                if matches!(
+                    archive.status,
+                    FileStatus::Downloaded | FileStatus::Installed
+                ) =>
+            {
+                bail!("'{}' was already fetched as '{}'", url, m.name)
+            }
+            Some(existing) => existing,
+            None => mod_list_store.add_local_source_archive(
+                &Mod {
+                    name: name.to_string(),
+                    source: Some(Source::Url),
+                    ..Default::default()
+                },
+                ModArchive {
+                    file_name: file_name.clone(),
+                    status: FileStatus::Unknown,
+                    url: Some(url.to_string()),
+                    ..Default::default()
+                },
+            )?,
+        };
+
+        let archive = ModArchive {
+            sha256: sha256.map(str::to_lowercase).or(archive.sha256),
+            ..archive
+        };
+        let staged = self.download_archive(mod_list_store, &m, archive).await?;
+
+        Ok((m, staged))
+    }
+
+    /// Claims an archive registered in the mod list, downloads it from its url and stages it,
+    /// returning the staged entry. Fails when the archive is already being downloaded or was
+    /// downloaded before, and marks it as failed when the download doesn't succeed.
+    pub async fn download_archive(
+        &self,
+        mod_list_store: &ModListStore,
+        m: &Mod,
+        archive: ModArchive,
+    ) -> anyhow::Result<ModArchive> {
+        match mod_list_store.claim_download(m, &archive)? {
+            FileStatus::Unknown | FileStatus::Failed(_) => {}
+            FileStatus::Downloading => bail!(
+                "There is already a download in progress for '{}'",
+                archive.file_name
+            ),
+            FileStatus::Downloaded | FileStatus::Installed => {
+                bail!("'{}' is already downloaded", archive.file_name)
+            }
+        }
+
+        let archive_path = mod_list_store.archive_download_dest(&archive.file_name);
+        let mut archive = ModArchive {
+            status: FileStatus::Downloading,
+            archive_path: Some(archive_path.clone()),
+            ..archive
+        };
+
+        let checksum = self
+            .download_tracker
+            .checked_download(m.uid, &archive, self.download(&archive, &archive_path))
+            .await?;
+
+        archive.size = Some(checksum.size);
+        archive.md5 = Some(checksum.md5);
+        mod_list_store.update_archive(&m.uid, &archive.file_uid, |a| {
+            a.size = archive.size;
+            a.md5 = archive.md5.clone();
+        })?;
+        mod_list_store.stage_archive(m, &archive)?;
+
+        mod_list_store
+            .read()?
+            .mods
+            .into_iter()
+            .find(|stored| stored.uid == m.uid)
+            .and_then(|stored| {
+                stored
+                    .archives
+                    .into_iter()
+                    .find(|a| a.file_uid == archive.file_uid)
+            })
+            .with_context(|| format!("'{}' is missing from the mod list", archive.file_name))
+    }
+
+    async fn download(
+        &self,
+        archive: &ModArchive,
+        archive_path: &Path,
+    ) -> anyhow::Result<crate::mods::download_tracker::Checksum> {
+        let url = Url::parse(archive.url.as_deref().unwrap_or_default())?;
+        let tracking_file = self
+            .download_tracker
+            .ensure_tracking_file(archive.file_uid)
+            .with_context(|| {
+                format!(
+                    "Could not resolve tracking file path for {}",
+                    archive.file_name
+                )
+            })?;
+
+        download::download_file(
+            &self.client,
+            &self.download_tracker,
+            &url,
+            archive_path,
+            &tracking_file,
+        )
+        .await
+    }
+}
+
+/// The archive's file name, taken from the last segment of the url path.
+pub fn archive_name(url: &Url) -> anyhow::Result<String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("Unsupported url '{}', expected http or https", url);
+    }
+
+    let file_name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .with_context(|| format!("Could not tell the archive name from '{}'", url))?
+        .to_string();
+
+    if !is_supported_archive(Path::new(&file_name)) {
+        bail!(
+            "'{}' is not a supported archive, expected one of: {}",
+            file_name,
+            ARCHIVE_EXTENSIONS.join(", ")
+        );
+    }
+
+    Ok(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config, GameConfig},
+        games::{Game, workspace::Workspace},
+    };
+    use mockito::Server;
+    use std::{fs, path::PathBuf};
+    use tempfile::TempDir;
+
+    const CONTENT: &[u8] = b"hello world";
+    const CONTENT_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+    fn setup_workspace() -> anyhow::Result<Workspace> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = Config::test_with_config(tmp_dir.path().to_owned(), game_config);
+        Workspace::new(&game, &config)
+    }
+
+    fn setup_downloader(workspace: &Workspace) -> anyhow::Result<(ModListStore, UrlDownloader)> {
+        let mod_list_store = ModListStore::new(workspace.clone());
+        let download_tracker = DownloadTracker::new(workspace.clone(), mod_list_store.clone());
+
+        Ok((mod_list_store, UrlDownloader::new(download_tracker)?))
+    }
+
+    #[test]
+    fn archive_name_should_use_last_path_segment() -> anyhow::Result<()> {
+        // Arrange
+        let url = Url::parse("https://example.com/files/skse64_2_02_06.7z?mirror=1")?;
+
+        // Act
+        let actual = archive_name(&url)?;
+
+        // Assert
+        assert_eq!(actual, "skse64_2_02_06.7z");
+
+        Ok(())
+    }
+
+    #[test]
+    fn archive_name_should_fail_when_not_an_archive() -> anyhow::Result<()> {
+        // Arrange
+        let urls = [
+            Url::parse("https://example.com/files/readme.txt")?,
+            Url::parse("https://example.com/")?,
+            Url::parse("ftp://example.com/mod.zip")?,
+        ];
+
+        // Act & Assert
+        for url in urls {
+            assert!(archive_name(&url).is_err(), "{} should be rejected", url);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_should_stage_archive_when_checksum_matches() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/files/Tool.zip")
+            .with_status(200)
+            .with_body(CONTENT)
+            .create_async()
+            .await;
+
+        let workspace = setup_workspace()?;
+        workspace.prepare_file_system()?;
+        let (mod_list_store, sut) = setup_downloader(&workspace)?;
+        let url = Url::parse(&format!("{}/files/Tool.zip", server.url()))?;
+
+        // Act
+        let (m, archive) = sut
+            .fetch(&mod_list_store, "Tool", &url, Some(CONTENT_SHA256))
+            .await?;
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(m.source(), Source::Url);
+        assert_eq!(archive.status, FileStatus::Downloaded);
+        assert_eq!(archive.url.as_deref(), Some(url.as_str()));
+        assert_eq!(archive.size, Some(CONTENT.len() as u64));
+        assert_eq!(fs::read(archive.archive_path.unwrap())?, CONTENT);
+        assert!(
+            sut.fetch(&mod_list_store, "Tool", &url, None)
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_should_mark_failed_when_checksum_mismatches() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/Tool.7z")
+            .with_status(200)
+            .with_body(CONTENT)
+            .create_async()
+            .await;
+
+        let workspace = setup_workspace()?;
+        workspace.prepare_file_system()?;
+        let (mod_list_store, sut) = setup_downloader(&workspace)?;
+        let url = Url::parse(&format!("{}/Tool.7z", server.url()))?;
+
+        // Act
+        let result = sut
+            .fetch(&mod_list_store, "Tool", &url, Some(&"0".repeat(64)))
+            .await;
+
+        // Assert
+        assert!(result.is_err());
+
+        let mods = mod_list_store.read()?.mods;
+        assert_eq!(mods.len(), 1);
+        assert!(matches!(mods[0].archives[0].status, FileStatus::Failed(_)));
+        assert!(!mod_list_store.archive_download_dest("Tool.7z").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_should_fail_when_download_is_in_progress() -> anyhow::Result<()> {
+        // Arrange
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/Tool.7z")
+            .with_status(200)
+            .with_body(CONTENT)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let workspace = setup_workspace()?;
+        workspace.prepare_file_system()?;
+        let (mod_list_store, sut) = setup_downloader(&workspace)?;
+        let url = Url::parse(&format!("{}/Tool.7z", server.url()))?;
+        mod_list_store.add_local_source_archive(
+            &Mod {
+                name: "Tool".to_string(),
+                source: Some(Source::Url),
+                ..Default::default()
+            },
+            ModArchive {
+                file_name: "Tool.7z".to_string(),
+                status: FileStatus::Downloading,
+                url: Some(url.to_string()),
+                ..Default::default()
+            },
+        )?;
+
+        // Act
+        let result = sut.fetch(&mod_list_store, "Tool", &url, None).await;
+
+        // Assert
+        assert!(result.is_err());
+        mock.assert_async().await;
+        assert_eq!(
+            mod_list_store.read()?.mods[0].archives[0].status,
+            FileStatus::Downloading
+        );
+
+        Ok(())
+    }
+}
@@ -2,24 +2,89 @@ use std::{fmt::Display, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone)]
+use crate::sources::Source;
+
+/// Mods that don't come from a mod source get uids from here on, well above any Nexus id.
+pub const LOCAL_UID_START: u64 = 1 << 48;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Mod {
     pub uid: u64,
     pub name: String,
     pub archives: Vec<ModArchive>,
+
+    /// Id of the mod on its mod source, unlike `uid` it isn't unique across games.
+    #[serde(default)]
+    pub mod_id: Option<u64>,
+
+    #[serde(default)]
+    pub author: Option<String>,
+
+    /// Latest version of the mod as declared by the mod source.
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Category id on the mod source.
+    #[serde(default)]
+    pub category_id: Option<u64>,
+
+    /// Short description from the mod page.
+    #[serde(default)]
+    pub summary: Option<String>,
+
+    /// Where the mod came from, mod lists written before this was stored don't have it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+
+    /// `owner/repo` of mods whose archives come from GitHub releases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+
+    /// Glob picking the release asset to download, e.g. `*-SE.7z`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_pattern: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ModArchive {
     pub file_uid: u64,
     pub file_name: String,
     pub archive_path: Option<PathBuf>,
     pub status: FileStatus,
+
+    /// MD5 hash of the archive as declared by the mod source.
+    #[serde(default)]
+    pub md5: Option<String>,
+
+    /// Size of the archive in bytes as declared by the mod source.
+    #[serde(default)]
+    pub size: Option<u64>,
+
+    /// Version of the file as declared by the mod source.
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// When the file was uploaded to the mod source, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub uploaded_at: Option<u64>,
+
+    /// File category on the mod source, e.g. `MAIN`, `OPTIONAL` or `OLD_VERSION`.
+    #[serde(default)]
+    pub category: Option<String>,
+
+    /// SHA-256 hash the download has to match, for sources that don't declare an MD5.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Where the archive was downloaded from, for sources without ids to look it up again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum FileStatus {
+    #[default]
     Unknown,
     Downloading,
     Downloaded,
@@ -27,12 +92,63 @@ pub enum FileStatus {
     Failed(String),
 }
 
+/// Kind of change made to the mod list.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Add,
+    Update,
+    Stage,
+    Install,
+    Uninstall,
+    Remove,
+}
+
+/// One change to an archive in the mod list, as recorded in the journal.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JournalEntry {
+    pub id: u64,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub operation: Operation,
+    pub mod_uid: u64,
+    pub file_uid: u64,
+    pub old_status: FileStatus,
+    pub new_status: FileStatus,
+    /// Id of the entry this change reverted, if it was made by an undo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverts: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ModList {
     pub mods: Vec<Mod>,
+
+    /// Folder names under the mods directory, from lowest to highest priority.
+    #[serde(default)]
+    pub priority: Vec<String>,
+}
+
+/// An installed mod folder that gets layered over the game.
+#[derive(Clone)]
+pub struct ModLayer {
+    pub dir_name: String,
+    pub label: String,
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PluginList {
+    pub plugins: Vec<Plugin>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Plugin {
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DownloadProgress {
     pub file_name: String,
     pub progress_bytes: u64,
@@ -41,6 +157,42 @@ pub struct DownloadProgress {
     pub updated_at: u64,
 }
 
+impl Mod {
+    /// Whether the mod was added locally instead of coming from a mod source.
+    pub fn is_local(&self) -> bool {
+        self.uid >= LOCAL_UID_START
+    }
+
+    /// Where the mod came from, guessed from the uid when it wasn't stored.
+    pub fn source(&self) -> Source {
+        match &self.source {
+            Some(source) => source.clone(),
+            None if self.is_local() => Source::Local,
+            None => Source::Nexus,
+        }
+    }
+
+    /// Whether the query is this mod's uid, its id on the mod source or, ignoring case, its name.
+    pub fn matches(&self, query: &str) -> bool {
+        query
+            .parse::<u64>()
+            .is_ok_and(|id| id == self.uid || Some(id) == self.mod_id)
+            || self.name.eq_ignore_ascii_case(query)
+    }
+
+    /// Takes over the metadata of `other`, keeping what it doesn't know.
+    pub fn update_metadata(&mut self, other: &Mod) {
+        self.name = other.name.clone();
+        self.mod_id = other.mod_id.or(self.mod_id);
+        self.author = other.author.clone().or(self.author.take());
+        self.version = other.version.clone().or(self.version.take());
+        self.category_id = other.category_id.or(self.category_id);
+        self.summary = other.summary.clone().or(self.summary.take());
+        self.repository = other.repository.clone().or(self.repository.take());
+        self.asset_pattern = other.asset_pattern.clone().or(self.asset_pattern.take());
+    }
+}
+
 impl Display for ModArchive {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.file_name)
@@ -53,6 +205,19 @@ impl Display for Mod {
     }
 }
 
+impl Display for ModLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+impl Display for Plugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let marker = if self.enabled { "x" } else { " " };
+        write!(f, "[{}] {}", marker, self.name)
+    }
+}
+
 impl Display for FileStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let message = match self {
@@ -67,8 +232,26 @@ impl Display for FileStatus {
     }
 }
 
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Operation::Add => "add",
+            Operation::Update => "update",
+            Operation::Stage => "stage",
+            Operation::Install => "install",
+            Operation::Uninstall => "uninstall",
+            Operation::Remove => "remove",
+        };
+
+        write!(f, "{}", message)
+    }
+}
+
 impl Default for ModList {
     fn default() -> Self {
-        Self { mods: vec![] }
+        Self {
+            mods: vec![],
+            priority: vec![],
+        }
     }
 }
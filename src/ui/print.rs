@@ -24,6 +24,16 @@ pub fn print_inline_status(message: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Short relative description of a duration in the past, e.g. `5m ago`.
+pub fn age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{}s ago", seconds),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
+
 fn hex_escape<T: AsRef<str>>(hex: &str, text: T) -> String {
     let hex = hex.trim_start_matches('#');
     let text = text.as_ref();
@@ -1,61 +1,47 @@
 use crossterm::style::Stylize;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 use crate::types::DownloadProgress;
 
 pub fn display_active_downloads(tracking_dir: &Path) -> anyhow::Result<()> {
+    display_downloads(|| read_tracking_dir(tracking_dir))
+}
+
+/// Shows a progress bar per download until interrupted. `poll` returns the downloads currently
+/// in flight keyed by a stable id; bars of downloads that are no longer returned are removed.
+pub fn display_downloads(
+    mut poll: impl FnMut() -> anyhow::Result<Vec<(u64, DownloadProgress)>>,
+) -> anyhow::Result<()> {
     let mp = MultiProgress::new();
     let pb_style = ProgressStyle::default_bar()
         .template("\n{msg} {bar:40.cyan/blue} {bytes}/{total_bytes}")
         .unwrap()
         .progress_chars("=> ");
 
-    let mut active_files: HashSet<u64> = HashSet::new();
-    let mut bars: HashMap<u64, (ProgressBar, VecDeque<(Instant, u64)>, PathBuf)> = HashMap::new();
+    let mut bars: HashMap<u64, (ProgressBar, VecDeque<(Instant, u64)>)> = HashMap::new();
 
     println!("\n{}", "Active downloads:".cyan().bold().underlined());
 
     loop {
-        for entry in std::fs::read_dir(tracking_dir)? {
-            let path = entry?.path();
-            if path.extension().map_or(true, |e| e != "json") {
-                continue;
-            }
+        let downloads = poll()?;
 
-            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                if let Ok(file_uid) = stem.parse::<u64>() {
-                    if !active_files.contains(&file_uid) {
-                        if let Ok(content) = std::fs::read_to_string(&path) {
-                            if let Ok(progress) = serde_json::from_str::<DownloadProgress>(&content)
-                            {
-                                let pb = mp.add(ProgressBar::new(progress.total_bytes));
-                                pb.set_style(pb_style.clone());
-                                pb.set_position(progress.progress_bytes);
-                                active_files.insert(file_uid);
-                                bars.insert(file_uid, (pb, VecDeque::new(), path.clone()));
-                            }
-                        }
-                    }
-                }
+        bars.retain(|id, (pb, _)| {
+            let active = downloads.iter().any(|(i, _)| i == id);
+            if !active {
+                pb.finish_and_clear();
             }
-        }
+            active
+        });
 
-        for (_uid, (pb, window, path)) in bars.iter_mut() {
-            let content = match std::fs::read_to_string(path) {
-                Ok(c) => c,
-                Err(_) => {
-                    pb.finish_and_clear();
-                    continue;
-                }
-            };
-
-            let progress: DownloadProgress = match serde_json::from_str(&content) {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
+        for (id, progress) in downloads {
+            let (pb, window) = bars.entry(id).or_insert_with(|| {
+                let pb = mp.add(ProgressBar::new(progress.total_bytes));
+                pb.set_style(pb_style.clone());
+                (pb, VecDeque::new())
+            });
 
             pb.set_length(progress.total_bytes);
             pb.set_position(progress.progress_bytes);
@@ -92,3 +78,31 @@ pub fn display_active_downloads(tracking_dir: &Path) -> anyhow::Result<()> {
         std::thread::sleep(Duration::from_millis(500));
     }
 }
+
+fn read_tracking_dir(tracking_dir: &Path) -> anyhow::Result<Vec<(u64, DownloadProgress)>> {
+    let mut downloads = Vec::new();
+
+    for entry in std::fs::read_dir(tracking_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+
+        let Some(file_uid) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u64>().ok())
+        else {
+            continue;
+        };
+
+        let progress = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str::<DownloadProgress>(&c).ok());
+        if let Some(progress) = progress {
+            downloads.push((file_uid, progress));
+        }
+    }
+
+    Ok(downloads)
+}
@@ -75,6 +75,24 @@ pub fn select<T: Display + Clone>(prompt: &str, options: &[T]) -> anyhow::Result
         .ok_or_else(|| anyhow::anyhow!("Invalid selection index"))
 }
 
+pub fn select_index<T: Display>(
+    prompt: &str,
+    options: &[T],
+    default: usize,
+) -> anyhow::Result<usize> {
+    let theme = crate::ui::theme::default_theme();
+
+    println!("{}", prompt.bold().cyan());
+
+    let index = Select::with_theme(&theme)
+        .items(options)
+        .report(false)
+        .default(default)
+        .interact()?;
+
+    Ok(index)
+}
+
 pub fn select_path(prompt: &str, entries: Vec<PathBuf>) -> anyhow::Result<PathBuf> {
     if entries.is_empty() {
         anyhow::bail!("No entries available to select from.");
@@ -140,6 +158,41 @@ pub fn select_multiple<T: Display + Clone>(prompt: &str, options: &[T]) -> anyho
     }
 }
 
+/// Keeps the order of the given options and returns the selected indices instead of the items.
+pub fn select_multiple_indices<T: Display>(
+    prompt: &str,
+    options: &[T],
+    defaults: &[bool],
+    allow_empty: bool,
+) -> anyhow::Result<Vec<usize>> {
+    let theme = theme::default_theme();
+
+    let mut errored = false;
+    loop {
+        let selection = MultiSelect::with_theme(&theme)
+            .with_prompt(prompt)
+            .items(options)
+            .defaults(defaults)
+            .report(false)
+            .interact()?;
+
+        if selection.is_empty() && !allow_empty {
+            let clear_lines = match errored {
+                true => 2,
+                false => 1,
+            };
+            clear_previous_lines(clear_lines, 0)?;
+            println!("{}", "Please select at least one option.".red());
+            errored = true;
+        } else {
+            if errored {
+                clear_previous_lines(1, 1)?;
+            }
+            return Ok(selection);
+        }
+    }
+}
+
 pub fn input(prompt: &str, allow_empty: bool) -> anyhow::Result<String> {
     let theme = theme::default_theme();
 
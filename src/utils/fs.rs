@@ -1,6 +1,8 @@
 use std::{
-    fs::{self, File},
-    io,
+    collections::BTreeSet,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    os::fd::AsRawFd,
     path::{Path, PathBuf},
     process::Command,
 };
@@ -21,6 +23,16 @@ impl ExpandTilde for Path {
     }
 }
 
+/// Archive formats `extract_archive` can unpack.
+pub const ARCHIVE_EXTENSIONS: [&str; 3] = ["zip", "7z", "rar"];
+
+/// Whether the file name ends in one of the `ARCHIVE_EXTENSIONS`, ignoring case.
+pub fn is_supported_archive(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| ARCHIVE_EXTENSIONS.contains(&e.as_str()))
+}
+
 pub fn extract_archive(
     archive_path: &Path,
     target_dir: &Path,
@@ -102,6 +114,147 @@ pub fn copy_dir(from: &Path, to: &Path, flatten_root: bool) -> io::Result<()> {
     Ok(())
 }
 
+/// Replaces the file at `path` with `contents` by writing a sibling temp file and renaming it over
+/// the original, so readers never see a partially written file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a file path", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let mut file = File::create(&tmp_path)
+        .with_context(|| format!("Failed to create '{}'", tmp_path.display()))?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to replace '{}'", path.display()))?;
+
+    Ok(())
+}
+
+/// Advisory lock on a file, held until dropped. Other processes taking the same lock wait for it.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    pub fn exclusive(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open lock file '{}'", path.display()))?;
+
+        // Closing the file releases the lock, so there is nothing to undo on drop.
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("Failed to lock '{}'", path.display()));
+        }
+
+        Ok(Self { _file: file })
+    }
+}
+
+/// What [`normalize_dir_case`] changed, as paths relative to its root.
+#[derive(Debug, Default, PartialEq)]
+pub struct NormalizedDirs {
+    /// Directories that were moved to their canonical name.
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+    /// Entries left behind because the canonical directory already contains the same name.
+    pub collisions: Vec<PathBuf>,
+}
+
+/// Renames every directory under `root` to the name returned by `canonical`, which receives the
+/// directory's path relative to `root`. Directories that fold onto an existing one are merged into
+/// it, keeping the file that was already there when both contain the same name.
+pub fn normalize_dir_case(
+    root: &Path,
+    canonical: &dyn Fn(&Path) -> String,
+) -> anyhow::Result<NormalizedDirs> {
+    let mut normalized = NormalizedDirs::default();
+    normalize_dir(root, Path::new(""), canonical, &mut normalized)?;
+
+    Ok(normalized)
+}
+
+fn normalize_dir(
+    root: &Path,
+    relative: &Path,
+    canonical: &dyn Fn(&Path) -> String,
+    normalized: &mut NormalizedDirs,
+) -> anyhow::Result<()> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(root.join(relative))? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+
+    // Siblings can fold onto the same directory, so only descend once all of them are merged.
+    let mut targets = BTreeSet::new();
+    for name in names {
+        let current = relative.join(&name);
+        let target = relative.join(canonical(&current));
+
+        if current != target {
+            let from = root.join(&current);
+            let to = root.join(&target);
+
+            if to.is_dir() {
+                merge_dir(root, &current, &target, &mut normalized.collisions)?;
+            } else {
+                fs::rename(&from, &to)?;
+            }
+
+            // A directory with collisions stays behind, so it wasn't renamed.
+            if !from.exists() {
+                normalized.renamed.push((current, target.clone()));
+            }
+        }
+
+        targets.insert(target);
+    }
+
+    for target in targets {
+        normalize_dir(root, &target, canonical, normalized)?;
+    }
+
+    Ok(())
+}
+
+/// Moves the contents of `from` into `to`, both relative to `root`, and removes `from` once it's
+/// empty. Entries that already exist in `to` are left in `from` and added to `collisions`.
+fn merge_dir(
+    root: &Path,
+    from: &Path,
+    to: &Path,
+    collisions: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(root.join(from))? {
+        let entry = entry?;
+        let source = from.join(entry.file_name());
+        let destination = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() && root.join(&destination).is_dir() {
+            merge_dir(root, &source, &destination, collisions)?;
+        } else if root.join(&destination).exists() {
+            collisions.push(source);
+        } else {
+            fs::rename(entry.path(), root.join(&destination))?;
+        }
+    }
+
+    if fs::read_dir(root.join(from))?.next().is_none() {
+        fs::remove_dir(root.join(from))?;
+    }
+
+    Ok(())
+}
+
 fn extract_zip(archive_path: &Path, target_dir: &Path) -> anyhow::Result<()> {
     let file = File::open(archive_path).context("Failed to open ZIP archive")?;
     let mut archive = ZipArchive::new(file).context("Failed to read ZIP archive")?;
@@ -216,6 +369,46 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn write_atomic_should_replace_contents_and_leave_no_temp_file() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("mod-list.json");
+        fs::write(&path, "old contents that are longer")?;
+
+        // Act
+        write_atomic(&path, b"new")?;
+
+        // Assert
+        assert_eq!(fs::read_to_string(&path)?, "new");
+        assert_eq!(fs::read_dir(tmp_dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn file_lock_should_block_other_lockers_until_dropped() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("mod-list.json.lock");
+        let lock = FileLock::exclusive(&path)?;
+
+        // Act
+        let other = std::thread::spawn({
+            let path = path.clone();
+            move || FileLock::exclusive(&path).map(|_| ())
+        });
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let finished_while_locked = other.is_finished();
+        drop(lock);
+
+        // Assert
+        assert!(!finished_while_locked);
+        other.join().unwrap()?;
+
+        Ok(())
+    }
+
     #[test]
     fn extract_archive_should_return_err_when_unsupported_extension_given() -> anyhow::Result<()> {
         // Arrange
@@ -492,4 +685,93 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn normalize_dir_case_should_rename_dirs_to_canonical_name() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup();
+        fs::create_dir_all(tmp_dir.path().join("DATA/Textures/Armor"))?;
+        fs::write(tmp_dir.path().join("DATA/Textures/Armor/a.dds"), "")?;
+
+        // Act
+        let normalized = normalize_dir_case(tmp_dir.path(), &|p| {
+            p.file_name().unwrap().to_string_lossy().to_lowercase()
+        })?;
+
+        // Assert
+        assert_eq!(
+            normalized.renamed,
+            vec![
+                (PathBuf::from("DATA"), PathBuf::from("data")),
+                (
+                    PathBuf::from("data/Textures"),
+                    PathBuf::from("data/textures")
+                ),
+                (
+                    PathBuf::from("data/textures/Armor"),
+                    PathBuf::from("data/textures/armor")
+                ),
+            ]
+        );
+        assert!(tmp_dir.path().join("data/textures/armor/a.dds").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_dir_case_should_merge_dirs_that_fold_onto_each_other() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup();
+        fs::create_dir_all(tmp_dir.path().join("Textures/Sub"))?;
+        fs::create_dir_all(tmp_dir.path().join("textures/sub"))?;
+        fs::create_dir_all(tmp_dir.path().join("TEXTURES"))?;
+        fs::write(tmp_dir.path().join("Textures/Sub/a.dds"), "")?;
+        fs::write(tmp_dir.path().join("textures/sub/b.dds"), "")?;
+        fs::write(tmp_dir.path().join("TEXTURES/c.dds"), "")?;
+
+        // Act
+        let normalized = normalize_dir_case(tmp_dir.path(), &|p| {
+            p.file_name().unwrap().to_string_lossy().to_lowercase()
+        })?;
+
+        // Assert
+        assert_eq!(normalized.renamed.len(), 3);
+        assert!(normalized.collisions.is_empty());
+        let entries: Vec<_> = fs::read_dir(tmp_dir.path())?.collect();
+        assert_eq!(entries.len(), 1);
+        assert!(tmp_dir.path().join("textures/sub/a.dds").exists());
+        assert!(tmp_dir.path().join("textures/sub/b.dds").exists());
+        assert!(tmp_dir.path().join("textures/c.dds").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_dir_case_should_report_collisions_instead_of_renames() -> anyhow::Result<()> {
+        // Arrange
+        let tmp_dir = setup();
+        fs::create_dir_all(tmp_dir.path().join("Textures"))?;
+        fs::create_dir_all(tmp_dir.path().join("textures"))?;
+        fs::write(tmp_dir.path().join("Textures/a.dds"), "upper")?;
+        fs::write(tmp_dir.path().join("textures/a.dds"), "lower")?;
+
+        // Act
+        let normalized = normalize_dir_case(tmp_dir.path(), &|p| {
+            p.file_name().unwrap().to_string_lossy().to_lowercase()
+        })?;
+
+        // Assert
+        assert_eq!(
+            fs::read_to_string(tmp_dir.path().join("textures/a.dds"))?,
+            "lower"
+        );
+        assert_eq!(
+            fs::read_to_string(tmp_dir.path().join("Textures/a.dds"))?,
+            "upper"
+        );
+        assert!(normalized.renamed.is_empty());
+        assert_eq!(normalized.collisions, vec![PathBuf::from("Textures/a.dds")]);
+
+        Ok(())
+    }
 }
@@ -37,7 +37,7 @@ mod tests {
         let usage = usage_for!(Cli::CONNECT);
 
         // Assert
-        assert_eq!(usage, "moma connect <SOURCE>");
+        assert_eq!(usage, "moma connect [OPTIONS] <SOURCE>");
     }
 
     #[test]
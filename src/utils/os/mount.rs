@@ -1,8 +1,24 @@
-use crate::{games::workspace::Workspace, utils::os::system_interface::SystemInterface};
-use std::process::Command;
+use anyhow::bail;
+
+use crate::{
+    games::workspace::Workspace,
+    utils::os::{permissions, system_interface::SystemInterface},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The kernel copies mount options into a single page, leave some room for the other options.
+const MAX_MOUNT_DATA: usize = 4000;
+
+/// Characters that separate layers or options in the mount data and can't be part of a layer.
+const RESERVED_PATH_CHARS: [char; 3] = [':', ',', '\\'];
 
 pub trait Mountable {
-    fn mount_overlay(&self) -> anyhow::Result<()>;
+    /// Mounts the given mod folders over the game, ordered from lowest to highest priority.
+    fn mount_overlay(&self, mod_dirs: &[PathBuf]) -> anyhow::Result<()>;
     fn unshare_as_private_namespace(&self) -> anyhow::Result<()>;
 }
 
@@ -15,27 +31,81 @@ impl<'a> OverlayMounter<'a> {
     pub fn new(workspace: &'a Workspace, system: &'a dyn SystemInterface) -> Self {
         Self { workspace, system }
     }
+
+    fn writable_options(&self) -> String {
+        format!(
+            "upperdir={},workdir={}",
+            self.workspace.sink_dir().display(),
+            self.workspace.overlay_work_dir().display(),
+        )
+    }
+
+    fn mount(&self, options: &str, target: &Path) -> anyhow::Result<()> {
+        let mut cmd = Command::new("mount");
+        cmd.args(["-t", "overlay", "overlay", "-o", options]);
+        cmd.arg(target);
+
+        self.system.run_command(&mut cmd)
+    }
+
+    /// Mounts chunks of the mod folders as read-only overlays, returns their mount points from
+    /// highest to lowest priority.
+    fn mount_nested(&self, lower_dirs: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+        let layers_dir = self.workspace.overlay_layers_dir();
+        let mut mount_points = Vec::new();
+
+        for chunk in chunk_by_length(lower_dirs, MAX_MOUNT_DATA) {
+            // A single lower layer isn't a valid read-only overlay, so it's used directly.
+            if chunk.len() == 1 {
+                mount_points.push(chunk[0].clone());
+                continue;
+            }
+
+            let mount_point = layers_dir.join(mount_points.len().to_string());
+            if !mount_point.exists() {
+                fs::create_dir_all(&mount_point)?;
+                permissions::chown_dir(&mount_point, false)?;
+            }
+
+            self.mount(&format!("lowerdir={}", join_paths(chunk)?), &mount_point)?;
+            mount_points.push(mount_point);
+        }
+
+        Ok(mount_points)
+    }
 }
 
 impl<'a> Mountable for OverlayMounter<'a> {
-    fn mount_overlay(&self) -> anyhow::Result<()> {
-        let mut cmd = Command::new("mount");
-        cmd.args([
-            "-t",
-            "overlay",
-            "overlay",
-            "-o",
+    fn mount_overlay(&self, mod_dirs: &[PathBuf]) -> anyhow::Result<()> {
+        // Overlayfs gives the leftmost lower layer the highest priority.
+        let mut lower_dirs: Vec<PathBuf> = mod_dirs.iter().rev().cloned().collect();
+        lower_dirs.push(self.workspace.game_dir());
+
+        let target = self.workspace.active_dir();
+
+        let options = format!(
+            "lowerdir={},{}",
+            join_paths(&lower_dirs)?,
+            self.writable_options()
+        );
+        if options.len() <= MAX_MOUNT_DATA {
+            return self.mount(&options, &target);
+        }
+
+        // `lowerdir+` would lift the limit, but only when mount(8) passes each option through the
+        // fsconfig API. Nesting works with every kernel and util-linux version.
+        let game_dir = lower_dirs.pop();
+        let mut nested = self.mount_nested(&lower_dirs)?;
+        nested.extend(game_dir);
+
+        self.mount(
             &format!(
-                "lowerdir={}:{},upperdir={},workdir={}",
-                self.workspace.overlay_merged_dir().display(),
-                self.workspace.game_dir().display(),
-                self.workspace.sink_dir().display(),
-                self.workspace.overlay_work_dir().display(),
+                "lowerdir={},{}",
+                join_paths(&nested)?,
+                self.writable_options()
             ),
-            self.workspace.active_dir().to_str().unwrap(),
-        ]);
-
-        self.system.run_command(&mut cmd)
+            &target,
+        )
     }
 
     fn unshare_as_private_namespace(&self) -> anyhow::Result<()> {
@@ -46,3 +116,235 @@ impl<'a> Mountable for OverlayMounter<'a> {
         self.system.run_command(&mut cmd)
     }
 }
+
+/// Joins the layers into a `lowerdir=` value. Paths with characters the mount data uses as
+/// separators are rejected, overlayfs would otherwise split them into other layers or options.
+fn join_paths(paths: &[PathBuf]) -> anyhow::Result<String> {
+    let mut joined = Vec::new();
+
+    for path in paths {
+        let path = path.display().to_string();
+        if path.contains(RESERVED_PATH_CHARS) {
+            bail!(
+                "Cannot mount '{}', overlay layers can't contain any of {:?}",
+                path,
+                RESERVED_PATH_CHARS
+            );
+        }
+        joined.push(path);
+    }
+
+    Ok(joined.join(":"))
+}
+
+/// Splits the paths into consecutive chunks whose `lowerdir=` option fits within `max_length`.
+fn chunk_by_length(paths: &[PathBuf], max_length: usize) -> Vec<&[PathBuf]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut length = "lowerdir=".len();
+
+    for (i, path) in paths.iter().enumerate() {
+        let path_length = path.as_os_str().len() + 1;
+
+        if i > start && length + path_length > max_length {
+            chunks.push(&paths[start..i]);
+            start = i;
+            length = "lowerdir=".len();
+        }
+
+        length += path_length;
+    }
+
+    if start < paths.len() {
+        chunks.push(&paths[start..]);
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{Config, GameConfig},
+        games::Game,
+    };
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    struct MockSystem {
+        commands: RefCell<Vec<Vec<String>>>,
+    }
+
+    impl MockSystem {
+        fn new() -> Self {
+            Self {
+                commands: RefCell::new(vec![]),
+            }
+        }
+
+        fn mount_options(&self) -> Vec<String> {
+            self.commands
+                .borrow()
+                .iter()
+                .filter_map(|args| {
+                    let index = args.iter().position(|a| a == "-o")?;
+                    args.get(index + 1).cloned()
+                })
+                .collect()
+        }
+    }
+
+    impl SystemInterface for MockSystem {
+        fn run_command(&self, cmd: &mut Command) -> anyhow::Result<()> {
+            self.commands.borrow_mut().push(
+                cmd.get_args()
+                    .map(|a| a.to_string_lossy().to_string())
+                    .collect(),
+            );
+            Ok(())
+        }
+
+        fn unshare_namespace(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn setup() -> anyhow::Result<(TempDir, Workspace)> {
+        let tmp_dir = TempDir::new()?;
+
+        let game = Game::SkyrimSE;
+        let game_config = GameConfig {
+            game: game.clone(),
+            path: PathBuf::from("/fake/skyrimse"),
+            proton_dir: PathBuf::from("/fake/proton"),
+            env: None,
+            sources: vec![],
+        };
+
+        let config = Config::test_with_config(tmp_dir.path().to_owned(), game_config);
+        let workspace = Workspace::new(&game, &config)?;
+
+        Ok((tmp_dir, workspace))
+    }
+
+    fn mod_dirs(workspace: &Workspace, amount: usize) -> Vec<PathBuf> {
+        (0..amount)
+            .map(|i| workspace.mods_dir().join(format!("{:0>40}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn mount_overlay_should_put_highest_priority_mod_first() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        let system = MockSystem::new();
+        let sut = OverlayMounter::new(&workspace, &system);
+
+        let low = workspace.mods_dir().join("low");
+        let high = workspace.mods_dir().join("high");
+
+        // Act
+        sut.mount_overlay(&[low.clone(), high.clone()])?;
+
+        // Assert
+        let expected = format!(
+            "lowerdir={}:{}:/fake/skyrimse,upperdir={},workdir={}",
+            high.display(),
+            low.display(),
+            workspace.sink_dir().display(),
+            workspace.overlay_work_dir().display()
+        );
+        assert_eq!(system.mount_options(), vec![expected]);
+
+        let commands = system.commands.borrow();
+        assert_eq!(
+            commands[0].last().unwrap(),
+            &workspace.active_dir().display().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn mount_overlay_should_only_mount_game_dir_when_no_mods_given() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        let system = MockSystem::new();
+        let sut = OverlayMounter::new(&workspace, &system);
+
+        // Act
+        sut.mount_overlay(&[])?;
+
+        // Assert
+        let options = system.mount_options();
+        assert!(options[0].starts_with("lowerdir=/fake/skyrimse,"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mount_overlay_should_nest_overlays_when_options_too_long() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        let system = MockSystem::new();
+        let sut = OverlayMounter::new(&workspace, &system);
+
+        let mods = mod_dirs(&workspace, 200);
+
+        // Act
+        sut.mount_overlay(&mods)?;
+
+        // Assert
+        let options = system.mount_options();
+        assert!(options.len() > 2);
+
+        let (nested, last) = options.split_at(options.len() - 1);
+        for option in nested {
+            assert!(option.starts_with("lowerdir="));
+            assert!(option.len() <= MAX_MOUNT_DATA);
+            assert!(!option.contains("upperdir="));
+        }
+        assert!(nested[0].starts_with(&format!("lowerdir={}:", mods[199].display())));
+
+        let layers_dir = workspace.overlay_layers_dir();
+        assert!(last[0].starts_with(&format!(
+            "lowerdir={}:{}",
+            layers_dir.join("0").display(),
+            layers_dir.join("1").display()
+        )));
+        assert!(last[0].contains(":/fake/skyrimse,upperdir="));
+
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_by_length_should_keep_every_chunk_within_max_length() {
+        // Arrange
+        let paths: Vec<PathBuf> = (0..10).map(|i| PathBuf::from(format!("/{}", i))).collect();
+
+        // Act
+        let actual = chunk_by_length(&paths, "lowerdir=".len() + 6);
+
+        // Assert
+        assert_eq!(actual.len(), 5);
+        assert_eq!(actual.iter().map(|c| c.len()).sum::<usize>(), 10);
+    }
+
+    #[test]
+    fn mount_overlay_should_return_err_when_layer_contains_separator() -> anyhow::Result<()> {
+        // Arrange
+        let (_tmp_dir, workspace) = setup()?;
+        let system = MockSystem::new();
+        let sut = OverlayMounter::new(&workspace, &system);
+
+        // Act & Assert
+        for name in ["with:colon", "with,comma"] {
+            let result = sut.mount_overlay(&[workspace.mods_dir().join(name)]);
+            assert!(result.is_err(), "{}", name);
+        }
+        assert!(system.mount_options().is_empty());
+
+        Ok(())
+    }
+}